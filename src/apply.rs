@@ -1,39 +1,50 @@
-use ::surrealdb::{
-    Connection, Surreal,
-    sql::{
-        Statement,
-        statements::{
-            DefineEventStatement, DefineFieldStatement, DefineStatement, DefineTableStatement,
-        },
-    },
-};
+use ::surrealdb::{Connection, Surreal, sql::Statement};
 use color_eyre::eyre::{ContextCompat, Result, eyre};
-use include_dir::Dir;
 use itertools::Itertools;
 use lexicmp::natural_lexical_cmp;
-use sha2::{Digest, Sha256};
 use std::{
     cmp::Ordering,
     collections::HashSet,
-    path::{Path, PathBuf},
+    path::Path,
+    time::{Duration, Instant},
 };
+use tracing::{Instrument, debug, info, info_span, warn};
 
 use crate::{
+    code_migration::{
+        NamedCodeMigration, ensure_code_migrations_names_are_unique, extract_code_migrations_files,
+        find_code_migration,
+    },
     common::get_migration_display_name,
     constants::{
-        ALL_TAGS, INITIAL_TRADITIONAL_MIGRATION_FILENAME, OLD_TAG, ROOT_TAG,
-        SCRIPT_MIGRATION_TABLE_NAME,
+        ALL_TAGS, DEFINITIONS_DIR_NAME, DOWN_TAG, INITIAL_TRADITIONAL_MIGRATION_FILENAME, OLD_TAG,
+        ROOT_TAG,
     },
+    error::MigrationError,
     file::SurqlFile,
     io::{
-        self, apply_patch, calculate_definition_using_patches, create_definition_files,
-        extract_json_definition_files, filter_except_initial_definition, get_current_definition,
-        get_initial_definition, get_migration_definition_diff,
+        self, apply_patch, calculate_definition_using_patches, extract_json_definition_files,
+        filter_except_initial_definition, get_current_definition, get_definition_files_changes,
+        get_initial_definition, get_migration_definition_diff, write_definition_files,
     },
-    models::{ApplyOperation, MigrationDirection, SchemaMigrationDefinition, ScriptMigration},
-    surrealdb::{
-        self, TransactionAction, get_surrealdb_table_definition, is_define_checksum_statement,
+    lock::{self, LockOptions},
+    migration_event::{NewMigrationEvent, record_migration_events},
+    models::{
+        ApplyOperation, ApplyReport, ChecksumAlgorithm, MigrationDirection, MigrationEventAction,
+        MigrationPlan, MigrationPlanStep, MigrationPlanStepKind, MigrationReport,
+        SchemaMigrationDefinition, ScriptMigration,
     },
+    observer::{MigrationObserver, notify_error},
+    rollback,
+    schema_snapshot::{get_snapshot_rollback_statements, take_schema_snapshot},
+    script_migration::{
+        ChecksumSupport, RunMetadata, create_script_migration_statement,
+        delete_script_migration_statement, get_latest_migration_applied,
+        set_migration_started_at_statement, update_script_migration_duration_statement,
+        upgrade_history_table_statements,
+    },
+    source::{FileSystemSource, MigrationSource, PlannedSource},
+    surrealdb::{self, TransactionAction, get_surrealdb_table_definition},
     validate_checksum::{self, ValidateChecksumArgs},
     validate_version_order::{self, ValidateVersionOrderArgs},
 };
@@ -41,8 +52,10 @@ use crate::{
 pub struct ApplyArgs<'a, C: Connection> {
     pub operation: ApplyOperation,
     pub db: &'a Surreal<C>,
-    pub dir: Option<&'a Dir<'static>>,
-    pub display_logs: bool,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
+    pub observer: Option<&'a dyn MigrationObserver>,
+    pub code_migrations: &'a [NamedCodeMigration<'a, C>],
     pub dry_run: bool,
     pub validate_checksum: bool,
     pub validate_version_order: bool,
@@ -50,14 +63,64 @@ pub struct ApplyArgs<'a, C: Connection> {
     pub output: bool,
     pub tags: Option<HashSet<String>>,
     pub exclude_tags: Option<HashSet<String>>,
+    pub lock: Option<LockOptions>,
+    pub single_transaction: bool,
+    pub checksum_algorithm: ChecksumAlgorithm,
+    pub out_of_order: bool,
+    pub executor: Option<String>,
+}
+
+pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<ApplyReport> {
+    let (report, _) = run(args, false).await?;
+    Ok(report)
+}
+
+pub async fn plan<C: Connection>(args: ApplyArgs<'_, C>) -> Result<MigrationPlan> {
+    let (_, plan) = run(args, true).await?;
+    Ok(plan)
+}
+
+async fn run<C: Connection>(
+    args: ApplyArgs<'_, C>,
+    plan_only: bool,
+) -> Result<(ApplyReport, MigrationPlan)> {
+    let span = info_span!(
+        "apply",
+        operation = ?args.operation,
+        dry_run = args.dry_run,
+        plan = plan_only,
+    );
+
+    // 💡 plans and dry runs leave the database untouched, so they do not take the lock
+    let lock_options = match plan_only || args.dry_run {
+        true => None,
+        false => args.lock,
+    };
+
+    let client = args.db;
+    let history_table = args.history_table;
+
+    lock::run_with_lock(
+        client,
+        history_table,
+        lock_options,
+        run_operation(args, plan_only),
+    )
+    .instrument(span)
+    .await
 }
 
-pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
+async fn run_operation<C: Connection>(
+    args: ApplyArgs<'_, C>,
+    plan_only: bool,
+) -> Result<(ApplyReport, MigrationPlan)> {
     let ApplyArgs {
         operation,
         db: client,
-        dir,
-        display_logs,
+        source,
+        history_table,
+        observer,
+        code_migrations,
         dry_run,
         validate_checksum,
         validate_version_order,
@@ -65,13 +128,19 @@ pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
         output,
         tags,
         exclude_tags,
+        lock: _,
+        single_transaction,
+        checksum_algorithm,
+        out_of_order,
+        executor,
     } = args;
 
     if validate_version_order {
         let validate_version_order_args = ValidateVersionOrderArgs {
             db: client,
-            dir,
-            config_file,
+            source,
+            history_table,
+            out_of_order,
         };
         validate_version_order::main(validate_version_order_args).await?;
     }
@@ -79,15 +148,15 @@ pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
     if validate_checksum {
         let validate_checksum_args = ValidateChecksumArgs {
             db: client,
-            dir,
-            config_file,
+            source,
+            history_table,
         };
         validate_checksum::main(validate_checksum_args).await?;
     }
 
-    let display_logs = match dry_run {
-        true => false,
-        false => display_logs,
+    let observer = match plan_only {
+        true => None,
+        false => observer,
     };
 
     let tags = match tags {
@@ -103,30 +172,22 @@ pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
         None => HashSet::from([OLD_TAG.into()]),
     };
 
-    let schemas_files = io::extract_schemas_files(config_file, dir, &tags, &exclude_tags)
+    let run_metadata = RunMetadata::new(executor, &tags);
+
+    let schemas_files = io::extract_schemas_files(source, &tags, &exclude_tags)
         .ok()
         .unwrap_or_default();
-    let events_files = io::extract_events_files(config_file, dir, &tags, &exclude_tags)
+    let events_files = io::extract_events_files(source, &tags, &exclude_tags)
         .ok()
         .unwrap_or_default();
 
     let schema_definitions = io::concat_files_content(&schemas_files);
     let event_definitions = io::concat_files_content(&events_files);
 
-    let forward_migrations_files = io::extract_migrations_files(
-        config_file,
-        dir,
-        MigrationDirection::Forward,
-        &tags,
-        &exclude_tags,
-    );
-    let backward_migrations_files = io::extract_migrations_files(
-        config_file,
-        dir,
-        MigrationDirection::Backward,
-        &tags,
-        &exclude_tags,
-    );
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags);
+    let backward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Backward, &tags, &exclude_tags);
 
     let use_traditional_approach = schema_definitions.is_empty()
         && event_definitions.is_empty()
@@ -136,18 +197,25 @@ pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
         use_traditional_approach,
         &schemas_files,
         &forward_migrations_files,
+        history_table,
     )?;
 
     let use_migration_definitions = !use_traditional_approach;
 
-    const DEFINITIONS_FOLDER: &str = "migrations/definitions";
-    let definitions_path = Path::new(DEFINITIONS_FOLDER);
+    let definitions_path = Path::new(DEFINITIONS_DIR_NAME);
 
     const INITIAL_DEFINITION_FILENAME: &str = "_initial.json";
     let initial_definition_path = definitions_path.join(INITIAL_DEFINITION_FILENAME);
 
+    let mut planned_definition_files_changes = None;
+
     if use_migration_definitions {
-        if io::can_use_filesystem(config_file)? {
+        let filesystem_source = match source.filesystem_root() {
+            Some(root) => FileSystemSource::new(root),
+            None => FileSystemSource::from_config_file(config_file),
+        };
+
+        if io::can_use_filesystem(&filesystem_source, history_table)? {
             let should_create_definition_files = match &operation {
                 ApplyOperation::Up | ApplyOperation::UpSingle | ApplyOperation::UpTo(_) => true,
                 ApplyOperation::Reset | ApplyOperation::DownSingle | ApplyOperation::DownTo(_) => {
@@ -156,8 +224,8 @@ pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
             };
 
             if should_create_definition_files {
-                create_definition_files(
-                    config_file,
+                let changes = get_definition_files_changes(
+                    &filesystem_source,
                     definitions_path.to_path_buf(),
                     initial_definition_path.to_path_buf(),
                     schema_definitions.to_string(),
@@ -165,20 +233,74 @@ pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
                     &tags,
                     &exclude_tags,
                 )?;
+
+                // 💡 a plan has no side effect, definition files are only written when applying migrations
+                match plan_only {
+                    true => planned_definition_files_changes = Some(changes),
+                    false => write_definition_files(&filesystem_source, changes)?,
+                }
             }
-        } else if let Some(dir) = dir {
+        } else {
             expect_migration_definitions_to_be_up_to_date(
                 schema_definitions.to_string(),
                 event_definitions.to_string(),
-                dir,
+                source,
             )?;
         }
     }
 
+    let planned_source =
+        planned_definition_files_changes.map(|changes| PlannedSource::new(source, changes));
+    let source: &dyn MigrationSource = match &planned_source {
+        Some(planned_source) => planned_source,
+        None => source,
+    };
+
     let migrations_applied =
-        surrealdb::list_script_migration_ordered_by_execution_date(client).await?;
+        surrealdb::list_script_migration_ordered_by_execution_date(client, history_table).await?;
+
+    let latest_migration_applied = get_latest_migration_applied(&migrations_applied);
+
+    let inferred_backward_migrations_files = match use_traditional_approach {
+        true => get_inferred_backward_migrations_files(
+            &forward_migrations_files,
+            &backward_migrations_files,
+            &migrations_applied,
+        ),
+        false => vec![],
+    };
+    let inferred_migrations = inferred_backward_migrations_files
+        .iter()
+        .map(|migration_file| migration_file.name.to_string())
+        .collect::<HashSet<_>>();
+
+    ensure_code_migrations_names_are_unique(code_migrations, &forward_migrations_files)?;
+
+    let forward_migrations_files = forward_migrations_files
+        .into_iter()
+        .chain(extract_code_migrations_files(
+            code_migrations,
+            MigrationDirection::Forward,
+            &tags,
+            &exclude_tags,
+        ))
+        .collect::<Vec<_>>();
+    let backward_migrations_files = backward_migrations_files
+        .into_iter()
+        .chain(inferred_backward_migrations_files)
+        .chain(extract_code_migrations_files(
+            code_migrations,
+            MigrationDirection::Backward,
+            &tags,
+            &exclude_tags,
+        ))
+        .collect::<Vec<_>>();
 
-    let last_migration_applied = migrations_applied.last();
+    let reversible_migrations = backward_migrations_files
+        .iter()
+        .filter(|migration_file| !inferred_migrations.contains(&migration_file.name))
+        .map(|migration_file| migration_file.name.to_string())
+        .collect::<HashSet<_>>();
 
     let migration_files_to_execute = get_migration_files_to_execute(
         forward_migrations_files,
@@ -187,42 +309,69 @@ pub async fn main<C: Connection>(args: ApplyArgs<'_, C>) -> Result<()> {
         &migrations_applied,
     );
 
+    if single_transaction {
+        ensure_single_transaction_is_supported(&migration_files_to_execute, code_migrations)?;
+    }
+
     let migration_direction = MigrationDirection::from(operation);
 
-    match migration_direction {
+    // 💡 older migrations are applied anyway, the out-of-order mode only makes it explicit
+    if migration_direction == MigrationDirection::Forward && !out_of_order {
+        let out_of_order_migrations = migration_files_to_execute
+            .iter()
+            .filter(|migration_file| is_out_of_order(migration_file, latest_migration_applied));
+
+        for migration_file in out_of_order_migrations {
+            warn!(
+                migration = migration_file.name,
+                "Applying a migration older than the last migration applied, use the out-of-order mode to make it explicit"
+            );
+        }
+    }
+
+    let result = match migration_direction {
         MigrationDirection::Forward => {
             apply_migrations(
-                config_file,
-                definitions_path.to_path_buf(),
                 migration_files_to_execute,
-                last_migration_applied,
-                display_logs,
+                latest_migration_applied,
+                observer,
+                code_migrations,
                 client,
                 dry_run,
-                dir,
+                source,
+                history_table,
                 output,
                 use_migration_definitions,
+                plan_only,
+                single_transaction,
+                checksum_algorithm,
+                &run_metadata,
+                &reversible_migrations,
             )
-            .await?;
+            .await?
         }
         MigrationDirection::Backward => {
             revert_migrations(
-                config_file,
-                definitions_path.to_path_buf(),
                 migration_files_to_execute,
                 &migrations_applied,
-                display_logs,
+                observer,
+                code_migrations,
                 client,
                 dry_run,
-                dir,
+                source,
+                history_table,
                 output,
                 use_migration_definitions,
+                plan_only,
+                single_transaction,
+                &run_metadata,
+                &inferred_migrations,
             )
-            .await?;
+            .await?
         }
-    }
+    };
 
-    Ok(())
+    Ok(result)
 }
 
 pub fn get_transaction_action(dry_run: bool) -> TransactionAction {
@@ -285,6 +434,40 @@ fn get_sorted_migrations_files(
     sorted_migrations_files
 }
 
+/// Migrations of a traditional project applied without a down migration file
+/// can still be reverted, using the schema snapshot taken before they were applied.
+fn get_inferred_backward_migrations_files(
+    forward_migrations_files: &[SurqlFile],
+    backward_migrations_files: &[SurqlFile],
+    migrations_applied: &[ScriptMigration],
+) -> Vec<SurqlFile> {
+    forward_migrations_files
+        .iter()
+        .filter(|migration_file| {
+            backward_migrations_files
+                .iter()
+                .all(|backward_migration_file| backward_migration_file.name != migration_file.name)
+        })
+        .filter(|migration_file| {
+            migrations_applied.iter().any(|migration_applied| {
+                migration_applied.script_name == migration_file.name
+                    && migration_applied.schema_snapshot.is_some()
+            })
+        })
+        .map(|migration_file| {
+            let mut tags = migration_file.tags.clone();
+            tags.insert(DOWN_TAG.to_string());
+
+            SurqlFile {
+                name: migration_file.name.to_string(),
+                full_name: migration_file.full_name.to_string(),
+                tags,
+                content: Box::new(|| Some(String::new())),
+            }
+        })
+        .collect()
+}
+
 fn filter_migration_file_to_execute(
     migration_file: &SurqlFile,
     operation: &ApplyOperation,
@@ -340,19 +523,27 @@ fn filter_migration_file_to_execute(
     }
 }
 
+fn is_out_of_order(
+    migration_file: &SurqlFile,
+    latest_migration_applied: Option<&ScriptMigration>,
+) -> bool {
+    match latest_migration_applied {
+        Some(latest_migration_applied) => {
+            natural_lexical_cmp(&migration_file.name, &latest_migration_applied.script_name)
+                == Ordering::Less
+        }
+        None => false,
+    }
+}
+
 fn expect_migration_definitions_to_be_up_to_date(
     schema_definitions: String,
     event_definitions: String,
-    embedded_dir: &Dir<'static>,
+    source: &dyn MigrationSource,
 ) -> Result<()> {
-    const DEFINITIONS_FOLDER: &str = "migrations/definitions";
-    let definitions_path = Path::new(DEFINITIONS_FOLDER);
-
-    let initial_definition =
-        get_initial_definition(None, definitions_path.to_path_buf(), Some(embedded_dir))?;
+    let initial_definition = get_initial_definition(source)?;
 
-    let mut definition_files =
-        extract_json_definition_files(None, definitions_path, Some(embedded_dir))?;
+    let mut definition_files = extract_json_definition_files(source)?;
     definition_files.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name));
     let definition_files = definition_files;
 
@@ -371,8 +562,7 @@ fn expect_migration_definitions_to_be_up_to_date(
     if is_up_to_date {
         Ok(())
     } else {
-        const ERROR_MESSAGE: &str = "The migration definitions are not up to date. Please run `surrealdb-migrations apply` on your local environment and publish definitions files.";
-        Err(eyre!(ERROR_MESSAGE))
+        Err(MigrationError::OutdatedDefinitions.into())
     }
 }
 
@@ -380,6 +570,7 @@ pub fn ensures_necessary_files_exists(
     use_traditional_approach: bool,
     schemas_files: &[SurqlFile],
     forward_migrations_files: &[SurqlFile],
+    history_table: &str,
 ) -> Result<()> {
     if use_traditional_approach {
         // expect __Initial.surql file (in migrations)
@@ -395,17 +586,14 @@ pub fn ensures_necessary_files_exists(
             ));
         }
     } else {
-        // expect script_migration.surql file (in schemas)
+        // expect script_migration.surql file (in schemas), named after the history table
         let has_necessary_files = schemas_files
             .iter()
             .filter(|f| !f.is_down_file())
-            .any(|f| f.name == SCRIPT_MIGRATION_TABLE_NAME);
+            .any(|f| f.name == history_table);
 
         if !has_necessary_files {
-            return Err(eyre!(
-                "The file '{}' should exist.",
-                SCRIPT_MIGRATION_TABLE_NAME
-            ));
+            return Err(eyre!("The file '{}' should exist.", history_table));
         }
     }
 
@@ -414,33 +602,36 @@ pub fn ensures_necessary_files_exists(
 
 #[allow(clippy::too_many_arguments)]
 async fn apply_migrations<C: Connection>(
-    config_file: Option<&Path>,
-    definitions_path: PathBuf,
     migration_files_to_execute: Vec<SurqlFile>,
-    last_migration_applied: Option<&ScriptMigration>,
-    display_logs: bool,
+    latest_migration_applied: Option<&ScriptMigration>,
+    observer: Option<&dyn MigrationObserver>,
+    code_migrations: &[NamedCodeMigration<'_, C>],
     client: &Surreal<C>,
     dry_run: bool,
-    embedded_dir: Option<&Dir<'static>>,
+    source: &dyn MigrationSource,
+    history_table: &str,
     output: bool,
     use_migration_definitions: bool,
-) -> Result<()> {
-    let mut has_applied_schemas = false;
-    let mut has_applied_events = false;
+    plan_only: bool,
+    single_transaction: bool,
+    checksum_algorithm: ChecksumAlgorithm,
+    run_metadata: &RunMetadata,
+    reversible_migrations: &HashSet<String>,
+) -> Result<(ApplyReport, MigrationPlan)> {
+    let mut report = ApplyReport {
+        dry_run,
+        ..Default::default()
+    };
+    let mut plan = MigrationPlan::default();
     let has_migration_files_to_execute = !migration_files_to_execute.is_empty();
     let mut current_definition: SchemaMigrationDefinition = Default::default();
 
     if use_migration_definitions {
-        current_definition = match last_migration_applied {
-            Some(last_migration_applied) => get_current_definition(
-                config_file,
-                definitions_path.to_path_buf(),
-                last_migration_applied,
-                embedded_dir,
-            ),
-            None => {
-                get_initial_definition(config_file, definitions_path.to_path_buf(), embedded_dir)
+        current_definition = match latest_migration_applied {
+            Some(latest_migration_applied) => {
+                get_current_definition(source, latest_migration_applied)
             }
+            None => get_initial_definition(source),
         }?;
 
         if !has_migration_files_to_execute {
@@ -453,65 +644,122 @@ async fn apply_migrations<C: Connection>(
         {events_statements}"
                 );
 
-                println!("-- Initial schema and event definitions --");
-                println!("{query}");
+                info!(statements = %query, "-- Initial schema and event definitions --");
             }
 
             let schemas_statements = surrealdb::parse_statements(&schemas_statements)?;
             let events_statements = surrealdb::parse_statements(&events_statements)?;
 
-            let statements = schemas_statements
-                .into_iter()
-                .chain(events_statements.into_iter())
-                .collect::<Vec<_>>();
+            let steps = vec![
+                create_plan_step(
+                    None,
+                    MigrationDirection::Forward,
+                    MigrationPlanStepKind::Schemas,
+                    schemas_statements.into_iter().collect(),
+                ),
+                create_plan_step(
+                    None,
+                    MigrationDirection::Forward,
+                    MigrationPlanStepKind::Events,
+                    events_statements.into_iter().collect(),
+                ),
+            ];
+            let statements = get_plan_steps_statements(&steps);
+            add_plan_steps(&mut plan, steps);
+
+            if plan_only {
+                return Ok((report, plan));
+            }
 
             let transaction_action = get_transaction_action(dry_run);
-            surrealdb::apply_in_transaction(client, statements, transaction_action).await?;
+            surrealdb::apply_in_transaction(client, statements, transaction_action, None).await?;
 
             if !current_definition.schemas.is_empty() {
-                has_applied_schemas = true;
+                report.schemas_applied = true;
             }
             if !current_definition.events.is_empty() {
-                has_applied_events = true;
+                report.events_applied = true;
             }
+
+            debug!("Schema and event definitions applied");
         }
     }
 
     let script_migration_table_definition =
-        get_surrealdb_table_definition(client, SCRIPT_MIGRATION_TABLE_NAME).await?;
-    let mut supports_checksum = script_migration_table_definition
-        .fields
-        .contains_key("checksum");
+        get_surrealdb_table_definition(client, history_table).await?;
+    let mut checksum_support =
+        ChecksumSupport::from_table_definition(&script_migration_table_definition);
+
+    // the history table is upgraded once, before the migrations are applied,
+    // or along with the first migration when the history table is defined by the migration itself
+    let mut upgrade_history_table = match has_migration_files_to_execute {
+        true => upgrade_history_table_statements(history_table),
+        false => vec![],
+    };
+
+    if !upgrade_history_table.is_empty()
+        && surrealdb::get_surrealdb_table_exists(client, history_table).await?
+    {
+        let steps = vec![create_plan_step(
+            None,
+            MigrationDirection::Forward,
+            MigrationPlanStepKind::ScriptMigration,
+            std::mem::take(&mut upgrade_history_table),
+        )];
+        let statements = get_plan_steps_statements(&steps);
+        add_plan_steps(&mut plan, steps);
+
+        if !plan_only {
+            let transaction_action = get_transaction_action(dry_run);
+            surrealdb::apply_in_transaction(client, statements, transaction_action, None).await?;
+        }
+    }
+
+    let mut batch_statements = Vec::new();
+    let mut batch_migrations = Vec::new();
 
     for migration_file in &migration_files_to_execute {
+        let out_of_order = is_out_of_order(migration_file, latest_migration_applied);
+
+        let migration_span = info_span!(
+            "migration",
+            name = %migration_file.name,
+            direction = ?MigrationDirection::Forward,
+            dry_run,
+            out_of_order,
+        );
+        let migration_display_name = get_migration_display_name(&migration_file.name);
+
         let mut schemas_statements = String::new();
         let mut events_statements = String::new();
 
         if use_migration_definitions {
-            let migration_definition_diff = get_migration_definition_diff(
-                config_file,
-                definitions_path.to_path_buf(),
-                migration_file.name.to_string(),
-                embedded_dir,
-            )?;
-
-            current_definition = match migration_definition_diff {
-                Some(migration_definition_diff) => {
-                    let schemas = match migration_definition_diff.schemas {
-                        Some(schemas_diff) => {
-                            apply_patch(current_definition.schemas, schemas_diff)?
-                        }
-                        None => current_definition.schemas,
-                    };
-                    let events = match migration_definition_diff.events {
-                        Some(events_diff) => apply_patch(current_definition.events, events_diff)?,
-                        None => current_definition.events,
-                    };
-
-                    SchemaMigrationDefinition { schemas, events }
-                }
-                None => current_definition,
-            };
+            // the definition of an out-of-order migration is already part of the latest definition,
+            // so the latest definition is applied again instead of going back in the patch chain
+            if !out_of_order {
+                let migration_definition_diff =
+                    get_migration_definition_diff(source, migration_file.name.to_string())?;
+
+                current_definition = match migration_definition_diff {
+                    Some(migration_definition_diff) => {
+                        let schemas = match migration_definition_diff.schemas {
+                            Some(schemas_diff) => {
+                                apply_patch(current_definition.schemas, schemas_diff)?
+                            }
+                            None => current_definition.schemas,
+                        };
+                        let events = match migration_definition_diff.events {
+                            Some(events_diff) => {
+                                apply_patch(current_definition.events, events_diff)?
+                            }
+                            None => current_definition.events,
+                        };
+
+                        SchemaMigrationDefinition { schemas, events }
+                    }
+                    None => current_definition,
+                };
+            }
 
             schemas_statements = current_definition.schemas.to_string();
             events_statements = current_definition.events.to_string();
@@ -520,9 +768,6 @@ async fn apply_migrations<C: Connection>(
         let migration_content = migration_file.get_content().unwrap_or(String::new());
 
         if output {
-            let migration_display_name = get_migration_display_name(&migration_file.name);
-            println!("-- Apply migration for {migration_display_name} --");
-
             let query = format!(
                 "{}
 {}
@@ -531,164 +776,331 @@ CREATE {} SET script_name = '{}';",
                 schemas_statements,
                 events_statements,
                 migration_content,
-                SCRIPT_MIGRATION_TABLE_NAME,
+                history_table,
                 migration_file.name
             );
-            println!("{query}");
+            migration_span.in_scope(
+                || info!(statements = %query, "-- Apply migration for {migration_display_name} --"),
+            );
+        }
+
+        if !dry_run && !plan_only {
+            migration_span.in_scope(|| info!("Executing migration {migration_display_name}..."));
         }
 
-        if display_logs {
-            let migration_display_name = get_migration_display_name(&migration_file.name);
-            println!("Executing migration {migration_display_name}...");
+        if let Some(observer) = observer {
+            observer.before_migration(&migration_file.name, MigrationDirection::Forward);
         }
 
         let schemas_statements = surrealdb::parse_statements(&schemas_statements)?;
         let events_statements = surrealdb::parse_statements(&events_statements)?;
-        let migration_statements = surrealdb::parse_statements(&migration_content)?;
+        let migration_statements =
+            surrealdb::parse_file_statements(&migration_file.name, &migration_content).map_err(
+                |error| {
+                    notify_error(
+                        observer,
+                        &migration_file.name,
+                        MigrationDirection::Forward,
+                        error,
+                    )
+                },
+            )?;
 
-        supports_checksum = supports_checksum
-            || schemas_statements.iter().any(is_define_checksum_statement)
-            || migration_statements
-                .iter()
-                .any(is_define_checksum_statement);
-
-        let mut what = ::surrealdb::sql::Values::default();
-        what.0.push(::surrealdb::sql::Value::Table(
-            SCRIPT_MIGRATION_TABLE_NAME.into(),
-        ));
-        let mut set_script_expressions = vec![(
-            ::surrealdb::sql::Idiom::from("script_name"),
-            ::surrealdb::sql::Operator::Equal,
-            ::surrealdb::sql::Value::Strand(migration_file.name.to_string().into()),
-        )];
-        if supports_checksum {
-            let checksum = Sha256::digest(migration_content).to_vec();
-            let checksum = hex::encode(checksum);
-
-            set_script_expressions.push((
-                ::surrealdb::sql::Idiom::from("checksum"),
-                ::surrealdb::sql::Operator::Equal,
-                ::surrealdb::sql::Value::Strand(checksum.into()),
+        checksum_support = checksum_support
+            .or(ChecksumSupport::from_statements(
+                &schemas_statements,
+                history_table,
+            ))
+            .or(ChecksumSupport::from_statements(
+                &migration_statements,
+                history_table,
             ));
-        }
-        let mut create_migration_script_statement =
-            ::surrealdb::sql::statements::CreateStatement::default();
-        create_migration_script_statement.what = what;
-        create_migration_script_statement.data = Some(::surrealdb::sql::Data::SetExpression(
-            set_script_expressions,
-        ));
-        create_migration_script_statement.output = Some(::surrealdb::sql::Output::None);
 
-        let statements = schemas_statements
-            .into_iter()
-            .chain(events_statements.into_iter())
-            .chain(migration_statements.into_iter())
-            .chain(
-                vec![::surrealdb::sql::Statement::Create(
-                    create_migration_script_statement,
-                )]
-                .into_iter(),
-            )
-            .collect::<Vec<_>>();
+        let code_migration = find_code_migration(code_migrations, &migration_file.name);
 
-        let transaction_action = get_transaction_action(dry_run);
-        surrealdb::apply_in_transaction(client, statements, transaction_action).await?;
+        let checksum = match code_migration {
+            Some(_) => None,
+            None => checksum_support.compute(&migration_content, checksum_algorithm)?,
+        };
+
+        // the schema of a traditional project is captured before a migration without down migration,
+        // to infer its rollback
+        // (not possible in a single transaction, where the previous migrations are not applied yet)
+        let should_take_schema_snapshot = !use_migration_definitions
+            && !single_transaction
+            && !plan_only
+            && !reversible_migrations.contains(&migration_file.name);
+        let schema_snapshot = match should_take_schema_snapshot {
+            true => Some(take_schema_snapshot(client, history_table).await?),
+            false => None,
+        };
+
+        let create_migration_script_statement = create_script_migration_statement(
+            history_table,
+            &migration_file.name,
+            checksum.as_ref(),
+            run_metadata,
+            schema_snapshot.as_deref(),
+        );
+        let checksum = checksum.map(|checksum| checksum.value);
+
+        let statements_count = migration_statements.len();
+
+        let migration_name = Some(migration_file.name.to_string());
+        let migration_step_kind = match code_migration {
+            Some(_) => MigrationPlanStepKind::CodeMigration,
+            None => MigrationPlanStepKind::Migration,
+        };
+
+        let steps = vec![
+            create_plan_step(
+                migration_name.clone(),
+                MigrationDirection::Forward,
+                MigrationPlanStepKind::Schemas,
+                schemas_statements.into_iter().collect(),
+            ),
+            create_plan_step(
+                migration_name.clone(),
+                MigrationDirection::Forward,
+                MigrationPlanStepKind::Events,
+                events_statements.into_iter().collect(),
+            ),
+            create_plan_step(
+                migration_name.clone(),
+                MigrationDirection::Forward,
+                migration_step_kind,
+                migration_statements.into_iter().collect(),
+            ),
+            create_plan_step(
+                migration_name,
+                MigrationDirection::Forward,
+                MigrationPlanStepKind::ScriptMigration,
+                std::mem::take(&mut upgrade_history_table)
+                    .into_iter()
+                    .chain([create_migration_script_statement])
+                    .collect(),
+            ),
+        ];
+        // 💡 the duration is stored in the transaction that records the migration
+        let store_duration_step = create_plan_step(
+            None,
+            MigrationDirection::Forward,
+            MigrationPlanStepKind::ScriptMigration,
+            vec![update_script_migration_duration_statement(
+                history_table,
+                &run_metadata.run_id,
+                &[&migration_file.name],
+            )],
+        );
+        let statements = get_execution_statements(
+            &[steps.as_slice(), &[store_duration_step]].concat(),
+            MigrationDirection::Forward,
+            migration_file.is_non_transactional(),
+        );
+        add_plan_steps(&mut plan, steps);
+
+        if plan_only {
+            continue;
+        }
 
         if use_migration_definitions {
             if !current_definition.schemas.is_empty() {
-                has_applied_schemas = true;
+                report.schemas_applied = true;
             }
             if !current_definition.events.is_empty() {
-                has_applied_events = true;
+                report.events_applied = true;
             }
         }
-    }
 
-    if display_logs {
-        if has_applied_schemas {
-            println!("Schema files successfully executed!");
+        if single_transaction {
+            batch_statements.push(set_migration_started_at_statement(Duration::ZERO));
+            batch_statements.extend(statements.before);
+            batch_migrations.push(MigrationReport {
+                name: migration_file.name.to_string(),
+                direction: MigrationDirection::Forward,
+                duration: Duration::ZERO,
+                checksum,
+                statements_count,
+                out_of_order,
+            });
+            continue;
         }
-        if has_applied_events {
-            println!("Event files successfully executed!");
+
+        let started_at = Instant::now();
+
+        let code_migration_up = match dry_run {
+            true => None,
+            false => code_migration.map(|code_migration| code_migration.up(client)),
+        };
+        let result = async {
+            if let Some(code_migration_up) = code_migration_up {
+                code_migration_up.await?;
+            }
+
+            execute_migration_statements(
+                client,
+                statements,
+                dry_run,
+                &migration_file.name,
+                Some(started_at),
+            )
+            .await
         }
-        if has_migration_files_to_execute {
-            println!("Migration files successfully executed!");
+        .instrument(migration_span.clone())
+        .await;
+
+        let events_recorded = match dry_run {
+            true => Ok(()),
+            false => {
+                let event = NewMigrationEvent::from_result(
+                    MigrationEventAction::Up,
+                    &migration_file.name,
+                    &result,
+                );
+                record_migration_events(client, history_table, run_metadata, vec![event]).await
+            }
+        };
+
+        result.map_err(|error| {
+            notify_error(
+                observer,
+                &migration_file.name,
+                MigrationDirection::Forward,
+                error,
+            )
+        })?;
+        events_recorded?;
+
+        let migration_report = MigrationReport {
+            name: migration_file.name.to_string(),
+            direction: MigrationDirection::Forward,
+            duration: started_at.elapsed(),
+            checksum,
+            statements_count,
+            out_of_order,
+        };
+
+        migration_span.in_scope(|| {
+            debug!(
+                duration_ms = migration_report.duration.as_millis() as u64,
+                statements_count,
+                checksum = migration_report.checksum.as_deref(),
+                "Migration applied"
+            )
+        });
+
+        if let Some(observer) = observer {
+            observer.after_migration(
+                &migration_file.name,
+                MigrationDirection::Forward,
+                &migration_report,
+            );
         }
+
+        report.migrations.push(migration_report);
     }
 
-    Ok(())
+    let batch_migrations = execute_in_single_transaction(
+        client,
+        batch_statements,
+        batch_migrations,
+        dry_run,
+        observer,
+        history_table,
+        run_metadata,
+    )
+    .await?;
+
+    report.migrations.extend(batch_migrations);
+
+    notify_schema_applied(observer, report.schemas_applied || report.events_applied);
+
+    Ok((report, plan))
 }
 
 #[allow(clippy::too_many_arguments)]
 async fn revert_migrations<C: Connection>(
-    config_file: Option<&Path>,
-    definitions_path: PathBuf,
     migration_files_to_execute: Vec<SurqlFile>,
     migrations_applied: &[ScriptMigration],
-    display_logs: bool,
+    observer: Option<&dyn MigrationObserver>,
+    code_migrations: &[NamedCodeMigration<'_, C>],
     client: &Surreal<C>,
     dry_run: bool,
-    embedded_dir: Option<&Dir<'static>>,
+    source: &dyn MigrationSource,
+    history_table: &str,
     output: bool,
     use_migration_definitions: bool,
-) -> Result<()> {
-    let last_migration_applied = migrations_applied.last();
+    plan_only: bool,
+    single_transaction: bool,
+    run_metadata: &RunMetadata,
+    inferred_migrations: &HashSet<String>,
+) -> Result<(ApplyReport, MigrationPlan)> {
+    let latest_migration_applied = get_latest_migration_applied(migrations_applied);
     let mut current_definition: SchemaMigrationDefinition = Default::default();
 
     if use_migration_definitions {
-        current_definition = match last_migration_applied {
-            Some(last_migration_applied) => get_current_definition(
-                config_file,
-                definitions_path.to_path_buf(),
-                last_migration_applied,
-                embedded_dir,
-            ),
-            None => {
-                get_initial_definition(config_file, definitions_path.to_path_buf(), embedded_dir)
+        current_definition = match latest_migration_applied {
+            Some(latest_migration_applied) => {
+                get_current_definition(source, latest_migration_applied)
             }
+            None => get_initial_definition(source),
         }?;
     }
 
-    let mut has_applied_schemas = false;
-    let mut has_applied_events = false;
-    let has_applied_migrations = !&migration_files_to_execute.is_empty();
+    let mut report = ApplyReport {
+        dry_run,
+        ..Default::default()
+    };
+    let mut plan = MigrationPlan::default();
+
+    let mut batch_statements = Vec::new();
+    let mut batch_migrations = Vec::new();
+
+    // the schema once the previous migrations are reverted, when it is known without querying the database
+    let mut current_schema_snapshot: Option<String> = None;
+    // the schema and event definitions are applied again after each migration reverted
+    let mut definitions_applied = false;
 
     for migration_file in &migration_files_to_execute {
+        let migration_span = info_span!(
+            "migration",
+            name = %migration_file.name,
+            direction = ?MigrationDirection::Backward,
+            dry_run,
+        );
+        let migration_display_name = get_migration_display_name(&migration_file.name);
+
         // TODO : optimize by getting the range of migration definitions before (avoid recalculation on each migration)
         let migration_reverted = migrations_applied
             .iter()
             .find(|migration_applied| migration_applied.script_name == migration_file.name)
             .context("Migration not found in applied migrations")?;
 
-        let migration_before_reverted = migrations_applied
+        let migrations_applied_before_reverted = migrations_applied
             .iter()
             .filter(|migration_applied| {
                 migration_applied.script_name < migration_reverted.script_name
             })
-            .next_back();
+            .cloned()
+            .collect::<Vec<_>>();
+        let migration_before_reverted =
+            get_latest_migration_applied(&migrations_applied_before_reverted);
 
         let mut definition_after_revert: SchemaMigrationDefinition = Default::default();
 
         if use_migration_definitions {
             definition_after_revert = match migration_before_reverted {
-                Some(migration_before_reverted) => get_current_definition(
-                    config_file,
-                    definitions_path.to_path_buf(),
-                    migration_before_reverted,
-                    embedded_dir,
-                ),
-                None => get_initial_definition(
-                    config_file,
-                    definitions_path.to_path_buf(),
-                    embedded_dir,
-                ),
+                Some(migration_before_reverted) => {
+                    get_current_definition(source, migration_before_reverted)
+                }
+                None => get_initial_definition(source),
             }?;
 
             if definition_after_revert.schemas != current_definition.schemas {
-                has_applied_schemas = true;
+                report.schemas_applied = true;
             }
             if definition_after_revert.events != current_definition.events {
-                has_applied_events = true;
+                report.events_applied = true;
             }
         }
 
@@ -698,7 +1110,29 @@ async fn revert_migrations<C: Connection>(
                 &current_definition.schemas,
                 &definition_after_revert.schemas,
             )?
+        } else if inferred_migrations.contains(&migration_file.name) {
+            migration_span.in_scope(|| {
+                warn!(
+                    "No down migration file found for {migration_display_name}, only the schema changes are reverted (data changes are not reverted)"
+                )
+            });
+
+            let schema_snapshot_before = match current_schema_snapshot.take() {
+                Some(schema_snapshot) => schema_snapshot,
+                None => take_schema_snapshot(client, history_table).await?,
+            };
+            let schema_snapshot_after = migration_reverted
+                .schema_snapshot
+                .clone()
+                .unwrap_or_default();
+
+            let statements =
+                get_snapshot_rollback_statements(&schema_snapshot_before, &schema_snapshot_after)?;
+            current_schema_snapshot = Some(schema_snapshot_after);
+
+            statements
         } else {
+            current_schema_snapshot = None;
             vec![]
         };
         let rollback_events_statements = if use_migration_definitions {
@@ -710,9 +1144,6 @@ async fn revert_migrations<C: Connection>(
         let events_statements_after_revert = definition_after_revert.events.to_string();
 
         if output {
-            let migration_display_name = get_migration_display_name(&migration_file.name);
-            println!("-- Revert migration for {migration_display_name} --");
-
             let query = format!(
                 "{}
 {}
@@ -731,220 +1162,455 @@ DELETE {} WHERE script_name = '{}';",
                     .join("\n"),
                 schemas_statements_after_revert,
                 events_statements_after_revert,
-                SCRIPT_MIGRATION_TABLE_NAME,
+                history_table,
                 migration_file.name
             );
-            println!("{query}");
+            migration_span.in_scope(|| {
+                info!(statements = %query, "-- Revert migration for {migration_display_name} --")
+            });
+        }
+
+        if !dry_run && !plan_only {
+            migration_span.in_scope(|| info!("Reverting migration {migration_display_name}..."));
         }
 
-        if display_logs {
-            let migration_display_name = get_migration_display_name(&migration_file.name);
-            println!("Reverting migration {migration_display_name}...");
+        if let Some(observer) = observer {
+            observer.before_migration(&migration_file.name, MigrationDirection::Backward);
         }
 
-        let migration_statements = surrealdb::parse_statements(&migration_statements)?;
+        let migration_statements =
+            surrealdb::parse_file_statements(&migration_file.name, &migration_statements).map_err(
+                |error| {
+                    notify_error(
+                        observer,
+                        &migration_file.name,
+                        MigrationDirection::Backward,
+                        error,
+                    )
+                },
+            )?;
         let schemas_statements_after_revert =
             surrealdb::parse_statements(&schemas_statements_after_revert)?;
         let events_statements_after_revert =
             surrealdb::parse_statements(&events_statements_after_revert)?;
 
-        let mut what = ::surrealdb::sql::Values::default();
-        what.0.push(::surrealdb::sql::Value::Table(
-            SCRIPT_MIGRATION_TABLE_NAME.into(),
-        ));
-        let mut cond = ::surrealdb::sql::Cond::default();
-        cond.0 =
-            ::surrealdb::sql::Value::Expression(Box::new(::surrealdb::sql::Expression::Binary {
-                l: ::surrealdb::sql::Value::Idiom("script_name".into()),
-                o: ::surrealdb::sql::Operator::Exact,
-                r: ::surrealdb::sql::Value::Strand(migration_file.name.to_string().into()),
-            }));
-        let mut delete_migration_script_statement =
-            ::surrealdb::sql::statements::DeleteStatement::default();
-        delete_migration_script_statement.what = what;
-        delete_migration_script_statement.cond = Some(cond);
-        delete_migration_script_statement.output = Some(::surrealdb::sql::Output::None);
-
-        let statements = vec![::surrealdb::sql::Statement::Delete(
-            delete_migration_script_statement,
-        )]
-        .into_iter()
-        .chain(migration_statements.into_iter())
-        .chain(rollback_schemas_statements.into_iter())
-        .chain(rollback_events_statements.into_iter())
-        .chain(schemas_statements_after_revert.into_iter())
-        .chain(events_statements_after_revert.into_iter())
-        .collect::<Vec<_>>();
+        let delete_migration_script_statement =
+            delete_script_migration_statement(history_table, &migration_file.name);
+
+        let statements_count = migration_statements.len();
 
-        let transaction_action = get_transaction_action(dry_run);
-        surrealdb::apply_in_transaction(client, statements, transaction_action).await?;
+        let code_migration = find_code_migration(code_migrations, &migration_file.name);
+
+        let migration_name = Some(migration_file.name.to_string());
+        let migration_step_kind = match code_migration {
+            Some(_) => MigrationPlanStepKind::CodeMigration,
+            None => MigrationPlanStepKind::Migration,
+        };
+
+        let steps = vec![
+            create_plan_step(
+                migration_name.clone(),
+                MigrationDirection::Backward,
+                MigrationPlanStepKind::ScriptMigration,
+                vec![delete_migration_script_statement],
+            ),
+            create_plan_step(
+                migration_name.clone(),
+                MigrationDirection::Backward,
+                migration_step_kind,
+                migration_statements.into_iter().collect(),
+            ),
+            create_plan_step(
+                migration_name.clone(),
+                MigrationDirection::Backward,
+                MigrationPlanStepKind::Rollback,
+                rollback_schemas_statements
+                    .into_iter()
+                    .chain(rollback_events_statements)
+                    .collect(),
+            ),
+            create_plan_step(
+                migration_name.clone(),
+                MigrationDirection::Backward,
+                MigrationPlanStepKind::Schemas,
+                schemas_statements_after_revert.into_iter().collect(),
+            ),
+            create_plan_step(
+                migration_name,
+                MigrationDirection::Backward,
+                MigrationPlanStepKind::Events,
+                events_statements_after_revert.into_iter().collect(),
+            ),
+        ];
+        let statements = get_execution_statements(
+            &steps,
+            MigrationDirection::Backward,
+            migration_file.is_non_transactional(),
+        );
+        add_plan_steps(&mut plan, steps);
 
         if use_migration_definitions {
             current_definition = definition_after_revert;
         }
-    }
 
-    if display_logs {
-        if has_applied_schemas {
-            println!("Schema files successfully executed!");
+        if plan_only {
+            continue;
         }
-        if has_applied_events {
-            println!("Event files successfully executed!");
+
+        if use_migration_definitions
+            && (!current_definition.schemas.is_empty() || !current_definition.events.is_empty())
+        {
+            definitions_applied = true;
+        }
+
+        if single_transaction {
+            batch_statements.push(set_migration_started_at_statement(Duration::ZERO));
+            batch_statements.extend(statements.before);
+            batch_migrations.push(MigrationReport {
+                name: migration_file.name.to_string(),
+                direction: MigrationDirection::Backward,
+                duration: Duration::ZERO,
+                checksum: None,
+                statements_count,
+                out_of_order: false,
+            });
+            continue;
+        }
+
+        let started_at = Instant::now();
+
+        let code_migration_down = match dry_run {
+            true => None,
+            false => code_migration.and_then(|code_migration| code_migration.down(client)),
+        };
+        let result = async {
+            if let Some(code_migration_down) = code_migration_down {
+                code_migration_down.await?;
+            }
+
+            execute_migration_statements(client, statements, dry_run, &migration_file.name, None)
+                .await
         }
-        if has_applied_migrations {
-            println!("Migration files successfully executed!");
+        .instrument(migration_span.clone())
+        .await;
+
+        let events_recorded = match dry_run {
+            true => Ok(()),
+            false => {
+                let event = NewMigrationEvent::from_result(
+                    MigrationEventAction::Down,
+                    &migration_file.name,
+                    &result,
+                );
+                record_migration_events(client, history_table, run_metadata, vec![event]).await
+            }
+        };
+
+        result.map_err(|error| {
+            notify_error(
+                observer,
+                &migration_file.name,
+                MigrationDirection::Backward,
+                error,
+            )
+        })?;
+        events_recorded?;
+
+        let migration_report = MigrationReport {
+            name: migration_file.name.to_string(),
+            direction: MigrationDirection::Backward,
+            duration: started_at.elapsed(),
+            checksum: None,
+            statements_count,
+            out_of_order: false,
+        };
+
+        migration_span.in_scope(|| {
+            debug!(
+                duration_ms = migration_report.duration.as_millis() as u64,
+                statements_count, "Migration reverted"
+            )
+        });
+
+        if let Some(observer) = observer {
+            observer.after_migration(
+                &migration_file.name,
+                MigrationDirection::Backward,
+                &migration_report,
+            );
         }
+
+        report.migrations.push(migration_report);
     }
 
-    Ok(())
+    let batch_migrations = execute_in_single_transaction(
+        client,
+        batch_statements,
+        batch_migrations,
+        dry_run,
+        observer,
+        history_table,
+        run_metadata,
+    )
+    .await?;
+    report.migrations.extend(batch_migrations);
+
+    notify_schema_applied(observer, definitions_applied);
+
+    Ok((report, plan))
 }
 
-fn get_rollback_statements(
-    next_statements_str: &str,
-    previous_statements_str: &str,
-) -> Result<Vec<::surrealdb::sql::Statement>> {
-    let next_statements = match next_statements_str.is_empty() {
-        true => vec![],
-        false => {
-            let next_statements = surrealdb::parse_statements(next_statements_str)?;
-            next_statements.0.0
+fn notify_schema_applied(observer: Option<&dyn MigrationObserver>, definitions_applied: bool) {
+    if let Some(observer) = observer {
+        if definitions_applied {
+            observer.on_schema_applied();
         }
-    };
+    }
+}
 
-    let previous_statements = match previous_statements_str.is_empty() {
-        true => vec![],
+fn ensure_single_transaction_is_supported<C: Connection>(
+    migration_files_to_execute: &[SurqlFile],
+    code_migrations: &[NamedCodeMigration<'_, C>],
+) -> Result<()> {
+    let unsupported_migration = migration_files_to_execute.iter().find(|migration_file| {
+        migration_file.is_non_transactional()
+            || find_code_migration(code_migrations, &migration_file.name).is_some()
+    });
+
+    match unsupported_migration {
+        Some(migration_file) => Err(eyre!(
+            "The migration '{}' cannot be executed inside a transaction, it cannot be applied in a single transaction.",
+            migration_file.name
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Executes the statements of all the migrations in a single transaction,
+/// so that either every migration is applied or none of them.
+async fn execute_in_single_transaction<C: Connection>(
+    client: &Surreal<C>,
+    statements: Vec<Statement>,
+    migrations: Vec<MigrationReport>,
+    dry_run: bool,
+    observer: Option<&dyn MigrationObserver>,
+    history_table: &str,
+    run_metadata: &RunMetadata,
+) -> Result<Vec<MigrationReport>> {
+    if migrations.is_empty() {
+        return Ok(migrations);
+    }
+
+    let started_at = Instant::now();
+
+    let transaction_action = get_transaction_action(dry_run);
+    let result = surrealdb::apply_in_transaction(client, statements, transaction_action, None)
+        .instrument(info_span!(
+            "single_transaction",
+            migrations = migrations.len()
+        ))
+        .await;
+
+    let events_recorded = match dry_run {
+        true => Ok(()),
         false => {
-            let previous_statements = surrealdb::parse_statements(previous_statements_str)?;
-            previous_statements.0.0
+            let events = migrations
+                .iter()
+                .map(|migration| {
+                    NewMigrationEvent::from_result(
+                        MigrationEventAction::from(migration.direction),
+                        &migration.name,
+                        &result,
+                    )
+                })
+                .collect();
+            record_migration_events(client, history_table, run_metadata, events).await
         }
     };
 
-    let next_tables_statements = extract_define_table_statements(next_statements.clone());
-    let previous_tables_statements = extract_define_table_statements(previous_statements.clone());
+    if let Err(error) = result {
+        // none of the migrations has been applied
+        let error = migrations.iter().fold(error, |error, migration| {
+            notify_error(observer, &migration.name, migration.direction, error)
+        });
+        return Err(error);
+    }
+    events_recorded?;
 
-    let tables_to_remove = next_tables_statements
-        .into_iter()
-        .filter(|next_table| {
-            previous_tables_statements
-                .iter()
-                .all(|previous_table| previous_table.name != next_table.name)
-        })
-        .map(|table| table.name.0)
-        .collect::<Vec<_>>();
+    let duration = started_at.elapsed();
 
-    let remove_table_statements = tables_to_remove
-        .clone()
+    let migrations = migrations
         .into_iter()
-        .map(|table_name| {
-            let mut s = ::surrealdb::sql::statements::RemoveTableStatement::default();
-            s.name = table_name.into();
-            ::surrealdb::sql::Statement::Remove(
-                ::surrealdb::sql::statements::RemoveStatement::Table(s),
-            )
+        .map(|migration| MigrationReport {
+            duration,
+            ..migration
         })
         .collect::<Vec<_>>();
 
-    let next_fields_statements = extract_define_field_statements(next_statements.clone());
-    let previous_fields_statements = extract_define_field_statements(previous_statements.clone());
+    for migration in &migrations {
+        debug!(
+            name = %migration.name,
+            direction = ?migration.direction,
+            duration_ms = duration.as_millis() as u64,
+            "Migration executed in a single transaction"
+        );
 
-    let fields_to_remove = next_fields_statements
-        .into_iter()
-        .filter(|next_field| {
-            !previous_fields_statements.iter().any(|previous_field| {
-                previous_field.name == next_field.name
-                    && previous_field.what.to_string() == next_field.what.to_string()
-            })
-        })
-        .map(|field| (field.name.to_string(), field.what.to_string()))
-        .filter(|(_, table_name)| !tables_to_remove.contains(table_name))
-        .collect::<Vec<_>>();
+        if let Some(observer) = observer {
+            observer.after_migration(&migration.name, migration.direction, migration);
+        }
+    }
 
-    let remove_fields_statements = fields_to_remove
-        .into_iter()
-        .map(|(field_name, table_name)| {
-            let mut s = ::surrealdb::sql::statements::RemoveFieldStatement::default();
-            s.name = field_name.into();
-            s.what = table_name.into();
-            ::surrealdb::sql::Statement::Remove(
-                ::surrealdb::sql::statements::RemoveStatement::Field(s),
-            )
-        })
-        .collect::<Vec<_>>();
+    Ok(migrations)
+}
 
-    let next_events_statements = extract_define_event_statements(next_statements);
-    let previous_events_statements = extract_define_event_statements(previous_statements);
+fn create_plan_step(
+    migration: Option<String>,
+    direction: MigrationDirection,
+    kind: MigrationPlanStepKind,
+    statements: Vec<Statement>,
+) -> MigrationPlanStep {
+    MigrationPlanStep {
+        migration,
+        direction,
+        kind,
+        statements,
+    }
+}
 
-    let events_to_remove = next_events_statements
-        .into_iter()
-        .filter(|next_event| {
-            !previous_events_statements.iter().any(|previous_event| {
-                previous_event.name == next_event.name
-                    && previous_event.what.to_string() == next_event.what.to_string()
-            })
-        })
-        .map(|event| (event.name.to_string(), event.what.to_string()))
-        .filter(|(_, table_name)| !tables_to_remove.contains(table_name))
-        .collect::<Vec<_>>();
+fn add_plan_steps(plan: &mut MigrationPlan, steps: Vec<MigrationPlanStep>) {
+    let steps = steps.into_iter().filter(|step| {
+        step.kind == MigrationPlanStepKind::CodeMigration || !step.statements.is_empty()
+    });
 
-    let remove_events_statements = events_to_remove
-        .into_iter()
-        .map(|(event_name, table_name)| {
-            let mut s = ::surrealdb::sql::statements::RemoveEventStatement::default();
-            s.name = event_name.into();
-            s.what = table_name.into();
-            ::surrealdb::sql::Statement::Remove(
-                ::surrealdb::sql::statements::RemoveStatement::Event(s),
-            )
-        })
-        .collect::<Vec<_>>();
+    plan.steps.extend(steps);
+}
 
-    let all_statements = remove_table_statements
-        .into_iter()
-        .chain(remove_fields_statements)
-        .chain(remove_events_statements)
-        .collect::<Vec<_>>();
+fn get_plan_steps_statements(steps: &[MigrationPlanStep]) -> Vec<Statement> {
+    steps
+        .iter()
+        .flat_map(|step| step.statements.iter().cloned())
+        .collect()
+}
 
-    Ok(all_statements)
+/// Statements of a migration, grouped by how they are executed.
+struct ExecutionStatements {
+    /// Statements executed in a transaction before the body of a non-transactional migration.
+    before: Vec<Statement>,
+    /// Body of a non-transactional migration, executed outside of a transaction.
+    non_transactional: Vec<Statement>,
+    /// Statements executed in a transaction after the body of a non-transactional migration.
+    after: Vec<Statement>,
 }
 
-fn extract_define_table_statements(statements: Vec<Statement>) -> Vec<DefineTableStatement> {
-    statements
-        .into_iter()
-        .filter_map(|statement| match statement {
-            Statement::Define(define_statement) => Some(define_statement),
-            _ => None,
-        })
-        .filter_map(|define_statement| match define_statement {
-            DefineStatement::Table(define_table_statement) => Some(define_table_statement),
-            _ => None,
-        })
-        .collect::<Vec<_>>()
+fn get_execution_statements(
+    steps: &[MigrationPlanStep],
+    direction: MigrationDirection,
+    is_non_transactional: bool,
+) -> ExecutionStatements {
+    if !is_non_transactional {
+        return ExecutionStatements {
+            before: get_plan_steps_statements(steps),
+            non_transactional: vec![],
+            after: vec![],
+        };
+    }
+
+    let (body_steps, other_steps): (Vec<_>, Vec<_>) = steps
+        .iter()
+        .cloned()
+        .partition(|step| step.kind == MigrationPlanStepKind::Migration);
+
+    // the migration is only recorded (or its record removed) once its body succeeded
+    let (before_steps, after_steps): (Vec<_>, Vec<_>) = match direction {
+        MigrationDirection::Forward => other_steps
+            .into_iter()
+            .partition(|step| step.kind != MigrationPlanStepKind::ScriptMigration),
+        MigrationDirection::Backward => (vec![], other_steps),
+    };
+
+    ExecutionStatements {
+        before: get_plan_steps_statements(&before_steps),
+        non_transactional: get_plan_steps_statements(&body_steps),
+        after: get_plan_steps_statements(&after_steps),
+    }
 }
 
-fn extract_define_field_statements(statements: Vec<Statement>) -> Vec<DefineFieldStatement> {
-    statements
-        .into_iter()
-        .filter_map(|statement| match statement {
-            Statement::Define(define_statement) => Some(define_statement),
-            _ => None,
-        })
-        .filter_map(|define_statement| match define_statement {
-            DefineStatement::Field(define_field_statement) => Some(define_field_statement),
-            _ => None,
-        })
-        .collect::<Vec<_>>()
+async fn execute_migration_statements<C: Connection>(
+    client: &Surreal<C>,
+    statements: ExecutionStatements,
+    dry_run: bool,
+    migration_name: &str,
+    started_at: Option<Instant>,
+) -> Result<()> {
+    if !statements.before.is_empty() {
+        surrealdb::apply_in_transaction(
+            client,
+            with_migration_started_at(statements.before, started_at),
+            get_transaction_action(dry_run),
+            Some(migration_name),
+        )
+        .await?;
+    }
+
+    // a non-transactional migration cannot be cancelled, so it is skipped on dry runs
+    if !statements.non_transactional.is_empty() && !dry_run {
+        warn!(
+            migration = migration_name,
+            "Executing migration outside of a transaction, a failure cannot be rolled back"
+        );
+        surrealdb::apply_without_transaction(client, statements.non_transactional, migration_name)
+            .await?;
+    }
+
+    if !statements.after.is_empty() {
+        surrealdb::apply_in_transaction(
+            client,
+            with_migration_started_at(statements.after, started_at),
+            get_transaction_action(dry_run),
+            Some(migration_name),
+        )
+        .await?;
+    }
+
+    Ok(())
 }
 
-fn extract_define_event_statements(statements: Vec<Statement>) -> Vec<DefineEventStatement> {
-    statements
-        .into_iter()
-        .filter_map(|statement| match statement {
-            Statement::Define(define_statement) => Some(define_statement),
-            _ => None,
-        })
-        .filter_map(|define_statement| match define_statement {
-            DefineStatement::Event(define_event_statement) => Some(define_event_statement),
-            _ => None,
-        })
-        .collect::<Vec<_>>()
+/// Variables are scoped to a query, so the start of the migration is set in each transaction.
+fn with_migration_started_at(
+    statements: Vec<Statement>,
+    started_at: Option<Instant>,
+) -> Vec<Statement> {
+    match started_at {
+        Some(started_at) => [set_migration_started_at_statement(started_at.elapsed())]
+            .into_iter()
+            .chain(statements)
+            .collect(),
+        None => statements,
+    }
+}
+
+fn get_rollback_statements(
+    next_statements_str: &str,
+    previous_statements_str: &str,
+) -> Result<Vec<::surrealdb::sql::Statement>> {
+    let next_statements = match next_statements_str.is_empty() {
+        true => vec![],
+        false => {
+            let next_statements = surrealdb::parse_statements(next_statements_str)?;
+            next_statements.0.0
+        }
+    };
+
+    let previous_statements = match previous_statements_str.is_empty() {
+        true => vec![],
+        false => {
+            let previous_statements = surrealdb::parse_statements(previous_statements_str)?;
+            previous_statements.0.0
+        }
+    };
+
+    Ok(rollback::get_rollback_statements(
+        next_statements,
+        previous_statements,
+    ))
 }
@@ -0,0 +1,164 @@
+use ::surrealdb::{Connection, Surreal};
+use color_eyre::eyre::{Result, eyre};
+use lexicmp::natural_lexical_cmp;
+use std::{cmp::Ordering, collections::HashSet};
+
+use crate::{
+    code_migration::{
+        NamedCodeMigration, ensure_code_migrations_names_are_unique, extract_code_migrations_files,
+        find_code_migration,
+    },
+    constants::ALL_TAGS,
+    error::MigrationError,
+    io,
+    lock::{self, LockOptions},
+    migration_event::{NewMigrationEvent, record_migration_events},
+    models::{ChecksumAlgorithm, MigrationDirection, MigrationEventAction},
+    script_migration::{
+        HistoryTableSetup, RunMetadata, create_script_migration_statement, prepare_history_table,
+    },
+    source::MigrationSource,
+    surrealdb::{self, TransactionAction},
+};
+
+pub struct BaselineArgs<'a, C: Connection> {
+    pub migration_name: String,
+    pub db: &'a Surreal<C>,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
+    pub code_migrations: &'a [NamedCodeMigration<'a, C>],
+    pub checksum_algorithm: ChecksumAlgorithm,
+    pub executor: Option<String>,
+    pub force: bool,
+    pub lock: Option<LockOptions>,
+}
+
+/// Records every migration up to and including the named migration as applied, without executing them.
+/// Returns the name of the migrations recorded.
+pub async fn main<C: Connection>(args: BaselineArgs<'_, C>) -> Result<Vec<String>> {
+    let client = args.db;
+    let history_table = args.history_table;
+    let lock = args.lock;
+
+    lock::run_with_lock(client, history_table, lock, baseline(args)).await
+}
+
+async fn baseline<C: Connection>(args: BaselineArgs<'_, C>) -> Result<Vec<String>> {
+    let BaselineArgs {
+        migration_name,
+        db: client,
+        source,
+        history_table,
+        code_migrations,
+        checksum_algorithm,
+        executor,
+        force,
+        lock: _,
+    } = args;
+
+    let tags = HashSet::from([ALL_TAGS.into()]);
+    let exclude_tags = HashSet::new();
+
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags);
+
+    ensure_code_migrations_names_are_unique(code_migrations, &forward_migrations_files)?;
+
+    let forward_migrations_files = io::get_sorted_migrations_files(
+        forward_migrations_files
+            .into_iter()
+            .chain(extract_code_migrations_files(
+                code_migrations,
+                MigrationDirection::Forward,
+                &tags,
+                &exclude_tags,
+            ))
+            .collect(),
+    );
+
+    let Some(baseline_migration) = forward_migrations_files
+        .iter()
+        .find(|f| f.name == migration_name || f.full_name == migration_name)
+    else {
+        return Err(MigrationError::UnknownMigration {
+            migration: migration_name,
+        }
+        .into());
+    };
+
+    let migrations_applied =
+        surrealdb::list_script_migration_ordered_by_execution_date(client, history_table).await?;
+
+    if !migrations_applied.is_empty() && !force {
+        return Err(eyre!(
+            "The migration history is not empty, {} migration(s) already applied. Use the force option to baseline anyway.",
+            migrations_applied.len()
+        ));
+    }
+
+    let migrations_files_to_baseline = forward_migrations_files
+        .iter()
+        .filter(|f| natural_lexical_cmp(&f.name, &baseline_migration.name) != Ordering::Greater)
+        .filter(|f| {
+            migrations_applied
+                .iter()
+                .all(|migration_applied| migration_applied.script_name != f.name)
+        })
+        .collect::<Vec<_>>();
+
+    let HistoryTableSetup {
+        mut statements,
+        checksum_support,
+    } = prepare_history_table(
+        client,
+        source,
+        history_table,
+        &migrations_files_to_baseline,
+        &tags,
+        &exclude_tags,
+    )
+    .await?;
+
+    let run_metadata = RunMetadata::new(executor, &tags);
+
+    for migration_file in &migrations_files_to_baseline {
+        let is_code_migration =
+            find_code_migration(code_migrations, &migration_file.name).is_some();
+
+        let checksum = match is_code_migration {
+            true => None,
+            false => checksum_support.compute(
+                &migration_file.get_content().unwrap_or_default(),
+                checksum_algorithm,
+            )?,
+        };
+
+        statements.push(create_script_migration_statement(
+            history_table,
+            &migration_file.name,
+            checksum.as_ref(),
+            &run_metadata,
+            None,
+        ));
+    }
+
+    let result =
+        surrealdb::apply_in_transaction(client, statements, TransactionAction::Commit, None).await;
+
+    let events = migrations_files_to_baseline
+        .iter()
+        .map(|f| NewMigrationEvent::from_result(MigrationEventAction::Baseline, &f.name, &result))
+        .collect();
+    let events_recorded =
+        record_migration_events(client, history_table, &run_metadata, events).await;
+
+    result?;
+    events_recorded?;
+
+    let migrations_baselined = migrations_files_to_baseline
+        .into_iter()
+        .map(|f| f.name.to_string())
+        .collect();
+
+    Ok(migrations_baselined)
+}
@@ -11,8 +11,8 @@ use crate::{
     },
     config,
     input::SurrealdbConfiguration,
-    io,
     models::Branch,
+    runbin::io::concat_path,
 };
 
 pub struct MergeOverwriteBranchArgs<'a> {
@@ -29,7 +29,7 @@ pub async fn main(args: MergeOverwriteBranchArgs<'_>) -> Result<()> {
     } = args;
 
     let folder_path = config::retrieve_folder_path(config_file);
-    let dump_file_path = io::concat_path(&folder_path, DUMP_FILENAME);
+    let dump_file_path = concat_path(&folder_path, DUMP_FILENAME);
 
     let branch_client = create_branch_client(config_file, &branch.name, &db_configuration).await?;
     branch_client.export(&dump_file_path).await?;
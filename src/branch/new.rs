@@ -13,9 +13,8 @@ use crate::{
     },
     config,
     input::SurrealdbConfiguration,
-    io,
     models::Branch,
-    runbin::{db_config::retrieve_db_config, surrealdb::create_surrealdb_client},
+    runbin::{db_config::retrieve_db_config, io::concat_path, surrealdb::create_surrealdb_client},
 };
 
 use super::{
@@ -40,7 +39,7 @@ pub async fn main(args: NewBranchArgs<'_>) -> Result<()> {
     let db_configuration = db_configuration.merge_with_config(&db_config);
 
     let folder_path = config::retrieve_folder_path(config_file);
-    let dump_file_path = io::concat_path(&folder_path, DUMP_FILENAME);
+    let dump_file_path = concat_path(&folder_path, DUMP_FILENAME);
 
     let branching_feature_client =
         create_branching_feature_client(config_file, &db_configuration).await?;
@@ -6,7 +6,7 @@ use clap::{Parser, Subcommand};
 use super::BranchArgs;
 #[cfg(feature = "scaffold")]
 use super::ScaffoldAction;
-use super::{ApplyArgs, CreateArgs, DiffArgs, ListArgs, StatusArgs};
+use super::{ApplyArgs, CreateArgs, DiffArgs, HistoryArgs, ListArgs, RepairArgs, StatusArgs};
 
 #[derive(Parser, Debug)]
 #[clap(name = "surrealdb-migrations", version, author = "Odonno")]
@@ -38,13 +38,17 @@ pub enum Action {
     Remove,
     /// Apply migration(s) to the database
     #[clap(aliases = vec!["a"])]
-    Apply(ApplyArgs),
+    Apply(Box<ApplyArgs>),
     /// List all migrations applied to the database
     #[clap(aliases = vec!["ls"])]
     List(ListArgs),
     /// Get a glimpse at the status of the database migrations
     #[clap(aliases = vec!["st"])]
     Status(StatusArgs),
+    /// Realign the migrations history with the migration files (checksums, deleted files)
+    Repair(RepairArgs),
+    /// Browse the log of every action applied to the migrations (up, down, redo, baseline, repair)
+    History(HistoryArgs),
     #[cfg(feature = "branching")]
     /// ** Preview ** A set of commands for database branching
     #[clap(aliases = vec!["b"])]
@@ -22,6 +22,22 @@ pub struct ApplyArgs {
     /// Please specify the name of the migration to re-apply.
     #[clap(long, conflicts_with_all = vec!["up", "down", "reset"])]
     pub redo: Option<String>,
+    /// Mark all migrations up to and including this migration name as applied, without executing them.
+    /// This parameter allows you to adopt migrations on an existing database.
+    #[clap(long, conflicts_with_all = vec!["up", "down", "reset", "redo", "dry_run", "plan", "single_transaction"])]
+    pub baseline: Option<String>,
+    /// Baseline even if the migration history is not empty.
+    /// Migrations already applied are left untouched.
+    #[clap(long, requires = "baseline")]
+    pub force: bool,
+    /// Mark a single migration as applied, without executing it.
+    /// Please specify the name of the migration to mark as applied.
+    #[clap(long, conflicts_with_all = vec!["up", "down", "reset", "redo", "baseline", "unfake", "dry_run", "plan", "single_transaction"])]
+    pub fake: Option<String>,
+    /// Remove a single migration from the migrations history, without reverting it.
+    /// Please specify the name of the migration to unmark.
+    #[clap(long, conflicts_with_all = vec!["up", "down", "reset", "redo", "baseline", "fake", "dry_run", "plan", "single_transaction"])]
+    pub unfake: Option<String>,
     /// Address of the surrealdb instance.
     /// Default value is `ws://localhost:8000`.
     #[clap(long)]
@@ -52,9 +68,20 @@ pub struct ApplyArgs {
     /// changes detected in the migrations directory.
     #[clap(long)]
     pub validate_version_order: bool,
+    /// Explicitly apply pending migrations older than the last migration applied.
+    /// By default, such migrations are applied with a warning, or rejected with `--validate-version-order`.
+    #[clap(long, conflicts_with_all = vec!["down", "reset", "redo"])]
+    pub out_of_order: bool,
     /// Output the surql statements to the console.
     #[clap(short, long, requires = "dry_run")]
     pub output: bool,
+    /// Display the statements that would be executed, step by step, without applying them.
+    #[clap(long, conflicts_with_all = vec!["redo", "dry_run"])]
+    pub plan: bool,
+    /// Apply all pending migrations in a single transaction.
+    /// If any statement fails, no migration is applied.
+    #[clap(long, conflicts_with = "redo")]
+    pub single_transaction: bool,
     /// A list of tags to use to include schemas and/or migration files, using "," as a delimiter.
     ///
     /// Note: The 'root' tag is always included.
@@ -65,4 +92,13 @@ pub struct ApplyArgs {
     /// Note: If no value provided, the 'old' tag is excluded.
     #[clap(long, value_delimiter = ',')]
     pub exclude_tags: Option<Vec<String>>,
+    /// Apply migrations without acquiring the migration lock.
+    /// The lock prevents concurrent migration runs on the same database,
+    /// including baseline, fake, unfake and redo operations.
+    #[clap(long)]
+    pub no_lock: bool,
+    /// Maximum time (in seconds) to wait for the migration lock held by another migration run.
+    /// Default value is `60`.
+    #[clap(long, conflicts_with = "no_lock")]
+    pub lock_timeout: Option<u64>,
 }
@@ -1,7 +1,26 @@
 use clap::Args;
 
+#[derive(clap::ValueEnum, Debug, Clone, Default)]
+pub enum DiffFormat {
+    #[default]
+    Text,
+    Json,
+}
+
 #[derive(Args, Debug)]
 pub struct DiffArgs {
     #[clap(long)]
     pub no_color: bool,
+    /// Output format of the differences
+    #[clap(long, value_enum, default_value_t)]
+    pub format: DiffFormat,
+    /// Exit with code 1 when differences are detected, and with code 2 on error
+    #[clap(long)]
+    pub exit_code: bool,
+    /// Generate a new migration file, with the given name, that reconciles the remote database with the project
+    #[clap(long, conflicts_with = "format")]
+    pub generate_migration: Option<String>,
+    /// Do not comment out the REMOVE statements of the generated migration file
+    #[clap(long, requires = "generate_migration")]
+    pub allow_destructive: bool,
 }
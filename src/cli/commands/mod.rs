@@ -2,7 +2,9 @@ mod apply;
 mod branch;
 mod create;
 mod diff;
+mod history;
 mod list;
+mod repair;
 mod scaffold;
 mod status;
 
@@ -11,7 +13,9 @@ pub use self::apply::*;
 pub use self::branch::*;
 pub use self::create::*;
 pub use self::diff::*;
+pub use self::history::*;
 pub use self::list::*;
+pub use self::repair::*;
 #[cfg(feature = "scaffold")]
 pub use self::scaffold::*;
 pub use self::status::*;
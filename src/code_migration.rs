@@ -0,0 +1,141 @@
+use ::surrealdb::{Connection, Surreal};
+use color_eyre::eyre::{Result, eyre};
+use std::{collections::HashSet, future::Future, pin::Pin};
+
+use crate::{
+    constants::{DOWN_TAG, ROOT_TAG},
+    file::SurqlFile,
+    models::MigrationDirection,
+};
+
+/// The future returned by a [`CodeMigration`].
+pub type MigrationFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// A migration written in Rust, for data migrations that cannot be expressed in SurrealQL.
+///
+/// Code migrations are ordered by name together with the `.surql` migration files
+/// and are tracked in the `script_migration` table like any other migration.
+/// Unlike `.surql` migrations, the Rust code is not executed inside the migration transaction
+/// and is skipped on dry runs.
+///
+/// Any function taking a `&Surreal<C>` and returning a [`MigrationFuture`] can be used as an up-only migration.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use color_eyre::eyre::{Result, eyre};
+/// use surrealdb_migrations::{CodeMigration, MigrationFuture, MigrationRunner};
+/// use surrealdb::engine::any::{connect, Any};
+/// use surrealdb::Surreal;
+///
+/// fn normalize_emails(db: &Surreal<Any>) -> MigrationFuture<'_> {
+///     Box::pin(async move {
+///         db.query("UPDATE user SET email = string::lowercase(email)").await?;
+///         Ok(())
+///     })
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let db = connect("ws://localhost:8000").await?;
+///
+/// MigrationRunner::new(&db)
+///     .with_code_migration("20240101_120000_NormalizeEmails", &normalize_emails)
+///     .up()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait CodeMigration<C: Connection>: Send + Sync {
+    /// Apply the migration.
+    fn up<'a>(&'a self, db: &'a Surreal<C>) -> MigrationFuture<'a>;
+
+    /// Revert the migration, or `None` (the default) if the migration cannot be reverted.
+    fn down<'a>(&'a self, _db: &'a Surreal<C>) -> Option<MigrationFuture<'a>> {
+        None
+    }
+
+    /// Whether the migration can be reverted, `false` by default.
+    /// It should return `true` when `down` is implemented.
+    fn has_down(&self) -> bool {
+        false
+    }
+}
+
+impl<C, F> CodeMigration<C> for F
+where
+    C: Connection,
+    F: for<'a> Fn(&'a Surreal<C>) -> MigrationFuture<'a> + Send + Sync,
+{
+    fn up<'a>(&'a self, db: &'a Surreal<C>) -> MigrationFuture<'a> {
+        self(db)
+    }
+
+    fn has_down(&self) -> bool {
+        false
+    }
+}
+
+pub struct NamedCodeMigration<'a, C: Connection> {
+    pub name: String,
+    pub migration: &'a dyn CodeMigration<C>,
+}
+
+pub fn find_code_migration<'a, 'b, C: Connection>(
+    code_migrations: &'b [NamedCodeMigration<'a, C>],
+    name: &str,
+) -> Option<&'b dyn CodeMigration<C>> {
+    code_migrations
+        .iter()
+        .find(|code_migration| code_migration.name == name)
+        .map(|code_migration| code_migration.migration)
+}
+
+pub fn extract_code_migrations_files<C: Connection>(
+    code_migrations: &[NamedCodeMigration<'_, C>],
+    migration_direction: MigrationDirection,
+    tags: &HashSet<String>,
+    exclude_tags: &HashSet<String>,
+) -> Vec<SurqlFile> {
+    code_migrations
+        .iter()
+        .filter(|code_migration| match migration_direction {
+            MigrationDirection::Forward => true,
+            MigrationDirection::Backward => code_migration.migration.has_down(),
+        })
+        .map(|code_migration| {
+            let mut file_tags = HashSet::from([ROOT_TAG.to_string()]);
+            if migration_direction == MigrationDirection::Backward {
+                file_tags.insert(DOWN_TAG.to_string());
+            }
+
+            SurqlFile {
+                name: code_migration.name.to_string(),
+                full_name: code_migration.name.to_string(),
+                tags: file_tags,
+                content: Box::new(|| None),
+            }
+        })
+        .filter(|file| file.filter_by_tags(tags, exclude_tags))
+        .collect()
+}
+
+/// A code migration cannot share its name with a migration file, both would be recorded as the same migration.
+pub fn ensure_code_migrations_names_are_unique<C: Connection>(
+    code_migrations: &[NamedCodeMigration<'_, C>],
+    migrations_files: &[SurqlFile],
+) -> Result<()> {
+    let duplicated_code_migration = code_migrations.iter().find(|code_migration| {
+        migrations_files
+            .iter()
+            .any(|migration_file| migration_file.name == code_migration.name)
+    });
+
+    match duplicated_code_migration {
+        Some(code_migration) => Err(eyre!(
+            "The code migration '{}' has the same name as a migration file.",
+            code_migration.name
+        )),
+        None => Ok(()),
+    }
+}
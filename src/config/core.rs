@@ -1,6 +1,7 @@
+use color_eyre::eyre::Result;
 use std::{env, path::Path};
 
-use crate::constants;
+use crate::{constants, error::MigrationError, models::ChecksumAlgorithm};
 
 use super::common::{load_config, retrieve_config_value};
 
@@ -13,3 +14,106 @@ pub fn retrieve_folder_path(config_file: Option<&Path>) -> Option<String> {
         env::var(constants::ENV_PATH).ok()
     }
 }
+
+pub fn retrieve_history_table(config_file: Option<&Path>) -> String {
+    let config = load_config(config_file);
+
+    let history_table = if let Some(config) = config {
+        retrieve_config_value(&config, "core", "history_table")
+            .or(env::var(constants::ENV_HISTORY_TABLE).ok())
+    } else {
+        env::var(constants::ENV_HISTORY_TABLE).ok()
+    };
+
+    history_table.unwrap_or(constants::SCRIPT_MIGRATION_TABLE_NAME.to_string())
+}
+
+pub fn retrieve_checksum_algorithm(config_file: Option<&Path>) -> Result<ChecksumAlgorithm> {
+    let config = load_config(config_file);
+
+    let checksum_algorithm = if let Some(config) = config {
+        retrieve_config_value(&config, "core", "checksum_algorithm")
+            .or(env::var(constants::ENV_CHECKSUM_ALGORITHM).ok())
+    } else {
+        env::var(constants::ENV_CHECKSUM_ALGORITHM).ok()
+    };
+
+    match checksum_algorithm {
+        Some(checksum_algorithm) => checksum_algorithm.parse().map_err(|_| {
+            MigrationError::InvalidConfigValue {
+                key: "checksum_algorithm".to_string(),
+                value: checksum_algorithm,
+            }
+            .into()
+        }),
+        None => Ok(ChecksumAlgorithm::default()),
+    }
+}
+
+pub fn retrieve_out_of_order(config_file: Option<&Path>) -> Result<bool> {
+    let config = load_config(config_file);
+
+    let out_of_order = if let Some(config) = config {
+        retrieve_config_value(&config, "core", "out_of_order")
+            .or(env::var(constants::ENV_OUT_OF_ORDER).ok())
+    } else {
+        env::var(constants::ENV_OUT_OF_ORDER).ok()
+    };
+
+    match out_of_order {
+        Some(out_of_order) => out_of_order.to_lowercase().parse().map_err(|_| {
+            MigrationError::InvalidConfigValue {
+                key: "out_of_order".to_string(),
+                value: out_of_order,
+            }
+            .into()
+        }),
+        None => Ok(false),
+    }
+}
+
+pub fn retrieve_executor(config_file: Option<&Path>) -> Option<String> {
+    let config = load_config(config_file);
+
+    if let Some(config) = config {
+        retrieve_config_value(&config, "core", "executor")
+            .or(env::var(constants::ENV_EXECUTOR).ok())
+    } else {
+        env::var(constants::ENV_EXECUTOR).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{NamedTempFile, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn fails_to_retrieve_invalid_checksum_algorithm() {
+        let config_file = NamedTempFile::new(".surrealdb").unwrap();
+        config_file
+            .write_str("[core]\nchecksum_algorithm=normalised\n")
+            .unwrap();
+
+        let error = retrieve_checksum_algorithm(Some(config_file.path())).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid value 'normalised' for the 'checksum_algorithm' configuration."
+        );
+    }
+
+    #[test]
+    fn fails_to_retrieve_invalid_out_of_order() {
+        let config_file = NamedTempFile::new(".surrealdb").unwrap();
+        config_file.write_str("[core]\nout_of_order=yes\n").unwrap();
+
+        let error = retrieve_out_of_order(Some(config_file.path())).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid value 'yes' for the 'out_of_order' configuration."
+        );
+    }
+}
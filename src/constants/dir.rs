@@ -2,3 +2,4 @@ pub const SCHEMAS_DIR_NAME: &str = "schemas";
 pub const EVENTS_DIR_NAME: &str = "events";
 pub const MIGRATIONS_DIR_NAME: &str = "migrations";
 pub const DOWN_MIGRATIONS_DIR_NAME: &str = "down";
+pub const DEFINITIONS_DIR_NAME: &str = "migrations/definitions";
@@ -1 +1,5 @@
 pub const ENV_PATH: &str = "SURREAL_MIG_PATH";
+pub const ENV_HISTORY_TABLE: &str = "SURREAL_MIG_HISTORY_TABLE";
+pub const ENV_CHECKSUM_ALGORITHM: &str = "SURREAL_MIG_CHECKSUM_ALGORITHM";
+pub const ENV_OUT_OF_ORDER: &str = "SURREAL_MIG_OUT_OF_ORDER";
+pub const ENV_EXECUTOR: &str = "SURREAL_MIG_EXECUTOR";
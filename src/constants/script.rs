@@ -1 +1,2 @@
 pub const SCRIPT_MIGRATION_TABLE_NAME: &str = "script_migration";
+pub const MIGRATION_LOCK_TABLE_NAME: &str = "migration_lock";
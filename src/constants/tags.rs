@@ -2,3 +2,4 @@ pub const ALL_TAGS: &str = "*";
 pub const ROOT_TAG: &str = "root";
 pub const DOWN_TAG: &str = "down";
 pub const OLD_TAG: &str = "old";
+pub const NO_TRANSACTION_TAG: &str = "notx";
@@ -10,8 +10,10 @@ use crate::{
         DOWN_MIGRATIONS_DIR_NAME, EVENTS_DIR_NAME, MIGRATIONS_DIR_NAME, SCHEMAS_DIR_NAME,
         SURQL_FILE_EXTENSION,
     },
-    io,
-    runbin::config::{retrieve_table_schema_design, TableSchemaDesign},
+    runbin::{
+        config::{retrieve_table_schema_design, TableSchemaDesign},
+        io::concat_path,
+    },
 };
 
 pub fn main(args: CreateArgs) -> Result<()> {
@@ -35,7 +37,7 @@ pub fn main(args: CreateArgs) -> Result<()> {
         CreateOperation::Migration(_) => MIGRATIONS_DIR_NAME,
     };
 
-    let folder_path = io::concat_path(&folder_path, dir_name);
+    let folder_path = concat_path(&folder_path, dir_name);
 
     let filename = get_filename(&operation, &name);
 
@@ -1,18 +1,30 @@
 use std::path::Path;
 
-use crate::cli;
+use crate::cli::{self, DiffFormat};
 
 pub struct DiffArgs<'a> {
     pub no_color: bool,
+    pub format: DiffFormat,
+    pub generate_migration: Option<String>,
+    pub allow_destructive: bool,
     pub config_file: Option<&'a Path>,
 }
 
 impl<'a> DiffArgs<'a> {
     pub fn from(value: cli::DiffArgs, config_file: Option<&'a Path>) -> Self {
-        let cli::DiffArgs { no_color } = value;
+        let cli::DiffArgs {
+            no_color,
+            format,
+            exit_code: _,
+            generate_migration,
+            allow_destructive,
+        } = value;
 
         DiffArgs {
             no_color,
+            format,
+            generate_migration,
+            allow_destructive,
             config_file,
         }
     }
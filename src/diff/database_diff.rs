@@ -0,0 +1,24 @@
+use std::fmt::{Display, Formatter};
+
+use super::definition_diff::{
+    DefinitionDiff, DefinitionKind, get_definitions_symbol, write_definitions_diff,
+};
+
+/// The differences on database-level definitions of the same kind (e.g. functions).
+pub struct DatabaseDiff {
+    pub kind: DefinitionKind,
+    pub definitions: Vec<DefinitionDiff>,
+}
+
+impl Display for DatabaseDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.definitions.is_empty() {
+            return Ok(());
+        }
+
+        let definitions = self.definitions.iter().collect::<Vec<_>>();
+        let symbol = get_definitions_symbol(&definitions);
+
+        write_definitions_diff(f, symbol, self.kind.plural(), &definitions)
+    }
+}
@@ -0,0 +1,259 @@
+use ::surrealdb::sql::statements::DefineStatement;
+use itertools::Itertools;
+use lexicmp::natural_lexical_cmp;
+use owo_colors::{self, AnsiColors, OwoColorize, Stream::Stdout};
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+use super::diff_symbol::DiffSymbol;
+use crate::rollback::normalize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DefinitionKind {
+    Table,
+    Field,
+    Index,
+    Event,
+    Analyzer,
+    Function,
+    Param,
+    Access,
+}
+
+impl DefinitionKind {
+    pub fn from_definition(definition: &DefineStatement) -> Option<Self> {
+        match definition {
+            DefineStatement::Table(_) => Some(DefinitionKind::Table),
+            DefineStatement::Field(_) => Some(DefinitionKind::Field),
+            DefineStatement::Index(_) => Some(DefinitionKind::Index),
+            DefineStatement::Event(_) => Some(DefinitionKind::Event),
+            DefineStatement::Analyzer(_) => Some(DefinitionKind::Analyzer),
+            DefineStatement::Function(_) => Some(DefinitionKind::Function),
+            DefineStatement::Param(_) => Some(DefinitionKind::Param),
+            DefineStatement::Access(_) => Some(DefinitionKind::Access),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DefinitionKind::Table => "table",
+            DefinitionKind::Field => "field",
+            DefinitionKind::Index => "index",
+            DefinitionKind::Event => "event",
+            DefinitionKind::Analyzer => "analyzer",
+            DefinitionKind::Function => "function",
+            DefinitionKind::Param => "param",
+            DefinitionKind::Access => "access",
+        }
+    }
+
+    pub fn plural(&self) -> &'static str {
+        match self {
+            DefinitionKind::Table => "tables",
+            DefinitionKind::Field => "fields",
+            DefinitionKind::Index => "indexes",
+            DefinitionKind::Event => "events",
+            DefinitionKind::Analyzer => "analyzers",
+            DefinitionKind::Function => "functions",
+            DefinitionKind::Param => "params",
+            DefinitionKind::Access => "accesses",
+        }
+    }
+}
+
+/// A definition that differs between the local project and the remote database.
+pub struct DefinitionDiff {
+    pub kind: DefinitionKind,
+    pub name: String,
+    /// The definition expected by the local project, if any.
+    pub local: Option<DefineStatement>,
+    /// The definition found in the remote database, if any.
+    pub remote: Option<DefineStatement>,
+}
+
+impl DefinitionDiff {
+    /// Returns `None` if both definitions are equivalent.
+    pub fn compare(
+        kind: DefinitionKind,
+        name: String,
+        local: Option<DefineStatement>,
+        remote: Option<DefineStatement>,
+    ) -> Option<Self> {
+        match (&local, &remote) {
+            (None, None) => return None,
+            (Some(local), Some(remote)) if are_equivalent(local, remote) => return None,
+            _ => {}
+        }
+
+        Some(DefinitionDiff {
+            kind,
+            name,
+            local,
+            remote,
+        })
+    }
+
+    pub fn symbol(&self) -> DiffSymbol {
+        match (&self.local, &self.remote) {
+            (Some(_), None) => DiffSymbol::Addition,
+            (None, Some(_)) => DiffSymbol::Deletion,
+            _ => DiffSymbol::Change,
+        }
+    }
+
+    /// The name as written in SurrealQL, e.g. `$limit` for a param or `fn::get_posts` for a function.
+    pub fn display_name(&self) -> String {
+        match self.kind {
+            DefinitionKind::Function => format!("fn::{}", self.name),
+            DefinitionKind::Param => format!("${}", self.name),
+            _ => self.name.to_string(),
+        }
+    }
+
+    fn label(&self) -> String {
+        match self.kind {
+            DefinitionKind::Table => {
+                let options = match (&self.local, &self.remote) {
+                    (Some(DefineStatement::Table(local)), Some(DefineStatement::Table(remote))) => {
+                        get_changed_table_options(local, remote)
+                    }
+                    _ => vec![],
+                };
+                format!("table options: {}", options.join(", "))
+            }
+            DefinitionKind::Index => format!("index {}", self.name),
+            DefinitionKind::Event => format!("event {}", self.name),
+            DefinitionKind::Field
+            | DefinitionKind::Function
+            | DefinitionKind::Param
+            | DefinitionKind::Analyzer
+            | DefinitionKind::Access => self.display_name(),
+        }
+    }
+}
+
+impl Display for DefinitionDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = self.symbol();
+
+        write!(
+            f,
+            "{}{}",
+            symbol,
+            self.label()
+                .if_supports_color(Stdout, |text| text.color(AnsiColors::from(symbol)))
+        )
+    }
+}
+
+/// Write a header (e.g. `~post (1 additions, 2 changes)`) followed by the list of definitions.
+pub fn write_definitions_diff(
+    f: &mut Formatter<'_>,
+    symbol: DiffSymbol,
+    name: &str,
+    definitions: &[&DefinitionDiff],
+) -> std::fmt::Result {
+    let count = |symbol: DiffSymbol| definitions.iter().filter(|d| d.symbol() == symbol).count();
+
+    let total_additions = count(DiffSymbol::Addition);
+    let total_changes = count(DiffSymbol::Change);
+    let total_deletions = count(DiffSymbol::Deletion);
+
+    let mut counts = Vec::with_capacity(3);
+    if total_additions > 0 {
+        counts.push(format!("{total_additions} additions"));
+    }
+    if total_changes > 0 {
+        counts.push(format!("{total_changes} changes"));
+    }
+    if total_deletions > 0 {
+        counts.push(format!("{total_deletions} deletions"));
+    }
+
+    let header_content = match counts.is_empty() {
+        true => name.to_string(),
+        false => format!("{} ({})", name, counts.join(", ")),
+    };
+
+    writeln!(
+        f,
+        "{}{}",
+        symbol,
+        header_content.if_supports_color(Stdout, |text| text.color(AnsiColors::from(symbol)))
+    )?;
+
+    for definition in definitions
+        .iter()
+        .sorted_by(|a, b| compare_definitions(a, b))
+    {
+        writeln!(f, "  {definition}")?;
+    }
+
+    Ok(())
+}
+
+/// Sort definitions by kind (table, fields, indexes, events, ...) then by name.
+pub fn compare_definitions(a: &DefinitionDiff, b: &DefinitionDiff) -> Ordering {
+    a.kind
+        .cmp(&b.kind)
+        .then_with(|| natural_lexical_cmp(&a.name, &b.name))
+}
+
+/// Returns the symbol matching the overall changes of a list of definitions.
+pub fn get_definitions_symbol(definitions: &[&DefinitionDiff]) -> DiffSymbol {
+    let has = |symbol: DiffSymbol| definitions.iter().any(|d| d.symbol() == symbol);
+
+    match (
+        has(DiffSymbol::Addition),
+        has(DiffSymbol::Change),
+        has(DiffSymbol::Deletion),
+    ) {
+        (_, false, false) => DiffSymbol::Addition,
+        (false, false, _) => DiffSymbol::Deletion,
+        _ => DiffSymbol::Change,
+    }
+}
+
+fn are_equivalent(local: &DefineStatement, remote: &DefineStatement) -> bool {
+    // 💡 secrets of access methods are redacted by the server
+    let local = match local {
+        DefineStatement::Access(access) => DefineStatement::Access(access.redacted()),
+        local => local.clone(),
+    };
+
+    normalize(&local).to_string() == normalize(remote).to_string()
+}
+
+fn get_changed_table_options(
+    local: &::surrealdb::sql::statements::DefineTableStatement,
+    remote: &::surrealdb::sql::statements::DefineTableStatement,
+) -> Vec<&'static str> {
+    let mut options = Vec::new();
+
+    if local.full != remote.full {
+        options.push("schema");
+    }
+    if local.kind != remote.kind {
+        options.push("type");
+    }
+    if local.view != remote.view {
+        options.push("view");
+    }
+    if local.drop != remote.drop {
+        options.push("drop");
+    }
+    if local.changefeed != remote.changefeed {
+        options.push("changefeed");
+    }
+    if local.permissions != remote.permissions {
+        options.push("permissions");
+    }
+    if local.comment != remote.comment {
+        options.push("comment");
+    }
+
+    options
+}
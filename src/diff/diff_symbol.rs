@@ -1,7 +1,7 @@
 use owo_colors::{self, AnsiColors, OwoColorize, Stream::Stdout};
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum DiffSymbol {
     Addition,
     Change,
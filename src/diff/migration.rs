@@ -0,0 +1,96 @@
+use ::surrealdb::sql::statements::DefineStatement;
+use itertools::Itertools;
+use lexicmp::natural_lexical_cmp;
+
+use super::{
+    database_diff::DatabaseDiff,
+    definition_diff::{DefinitionDiff, compare_definitions},
+    diff_symbol::DiffSymbol,
+    table_diff::TableDiff,
+};
+use crate::rollback::{get_remove_statement, normalize, set_overwrite};
+
+/// Generate the content of a migration that applies the local definitions to the remote database:
+/// definitions added or changed are (re)defined, definitions deleted are removed.
+/// REMOVE statements are commented out, unless `allow_destructive` is set.
+pub fn generate_migration_content(
+    table_diffs: &[TableDiff],
+    database_diffs: &[DatabaseDiff],
+    allow_destructive: bool,
+) -> String {
+    let table_diffs = table_diffs
+        .iter()
+        .sorted_by(|a, b| natural_lexical_cmp(&a.name, &b.name))
+        .collect_vec();
+
+    let database_definitions = database_diffs
+        .iter()
+        .flat_map(|database_diff| {
+            database_diff
+                .definitions
+                .iter()
+                .sorted_by(|a, b| compare_definitions(a, b))
+        })
+        .collect_vec();
+
+    let table_definitions = table_diffs
+        .iter()
+        .flat_map(|table_diff| {
+            let definitions = match table_diff.table.as_ref().map(|table| table.symbol()) {
+                // 💡 removing a table removes its fields, indexes and events
+                Some(DiffSymbol::Deletion) => vec![],
+                _ => table_diff
+                    .definitions
+                    .iter()
+                    .sorted_by(|a, b| compare_definitions(a, b))
+                    .collect_vec(),
+            };
+
+            table_diff.table.iter().chain(definitions)
+        })
+        .collect_vec();
+
+    let define_statements = database_definitions
+        .iter()
+        .chain(table_definitions.iter())
+        .filter_map(|definition| definition.local.as_ref())
+        .map(|definition| {
+            let mut definition = normalize(definition);
+            set_overwrite(&mut definition);
+            format!("{definition};")
+        })
+        .collect_vec();
+
+    let remove_statements = table_definitions
+        .iter()
+        .rev()
+        .chain(database_definitions.iter().rev())
+        .filter(|definition| definition.symbol() == DiffSymbol::Deletion)
+        .filter_map(|definition| get_deleted_definition_remove_statement(definition))
+        .map(|statement| match allow_destructive {
+            true => format!("{statement};"),
+            false => format!("# {statement};"),
+        })
+        .collect_vec();
+
+    let mut sections = Vec::with_capacity(2);
+    if !define_statements.is_empty() {
+        sections.push(define_statements.join("\n"));
+    }
+    if !remove_statements.is_empty() {
+        let header = match allow_destructive {
+            true => None,
+            false => Some(
+                "# Destructive statements are commented out, uncomment them to apply".to_string(),
+            ),
+        };
+        sections.push(header.into_iter().chain(remove_statements).join("\n"));
+    }
+
+    sections.join("\n\n")
+}
+
+fn get_deleted_definition_remove_statement(definition: &DefinitionDiff) -> Option<String> {
+    let remote: &DefineStatement = definition.remote.as_ref()?;
+    get_remove_statement(remote).map(|statement| statement.to_string())
+}
@@ -1,30 +1,71 @@
 mod args;
+mod database_diff;
+mod definition_diff;
 mod diff_symbol;
+mod migration;
+mod report;
 mod table_diff;
+mod traditional;
 
+use ::surrealdb::sql::{Base, Statement, statements::DefineStatement};
 pub use args::*;
 use color_eyre::eyre::Result;
+use database_diff::DatabaseDiff;
+use definition_diff::{DefinitionDiff, DefinitionKind};
 use futures::future::join_all;
 use itertools::Itertools;
 use lexicmp::natural_lexical_cmp;
+use report::DiffReport;
 use std::{collections::HashSet, path::Path};
 use table_diff::TableDiff;
 
 use crate::{
     apply::ensures_necessary_files_exists,
-    constants::ALL_TAGS,
+    cli::DiffFormat,
+    config,
+    constants::{ALL_TAGS, MIGRATION_LOCK_TABLE_NAME},
+    create::{self, CreateArgs, CreateMigrationArgs, CreateOperation},
     input::SurrealdbConfiguration,
     io,
+    migration_event::get_event_table_name,
     models::MigrationDirection,
+    rollback::get_definition_key,
     runbin::surrealdb::create_surrealdb_client,
+    source::FileSystemSource,
     surrealdb::{
-        get_surrealdb_table_definition, get_surrealdb_table_definitions, parse_statements,
+        get_surrealdb_database_definition, get_surrealdb_table_definition, parse_file_statements,
+        parse_statements,
     },
 };
 
-pub async fn main(args: DiffArgs<'_>) -> Result<()> {
+/// Whether differences were detected, to set the exit code of `--exit-code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+    NoChanges,
+    Changes,
+}
+
+/// Exit like `git diff --exit-code`: 0 without differences, 1 with differences and 2 on error.
+pub fn exit_with_status(result: Result<DiffStatus>) -> ! {
+    // 💡 differences are not an error, so no error report is displayed for them
+    let code = match result {
+        Ok(DiffStatus::NoChanges) => 0,
+        Ok(DiffStatus::Changes) => 1,
+        Err(error) => {
+            eprintln!("Error: {error:?}");
+            2
+        }
+    };
+
+    std::process::exit(code)
+}
+
+pub async fn main(args: DiffArgs<'_>) -> Result<DiffStatus> {
     let DiffArgs {
         no_color,
+        format,
+        generate_migration,
+        allow_destructive,
         config_file,
     } = args;
 
@@ -35,106 +76,260 @@ pub async fn main(args: DiffArgs<'_>) -> Result<()> {
     let local_statements = get_local_statements(config_file)?;
     let remote_statements = get_remote_statements(config_file).await?;
 
-    let local_table_names = local_statements.iter().filter_map(map_table_name);
-    let remote_table_names = remote_statements.iter().filter_map(map_table_name);
+    let local_definitions = local_statements
+        .into_iter()
+        .filter_map(map_define_statement)
+        .collect_vec();
+    let remote_definitions = remote_statements
+        .into_iter()
+        .filter_map(map_define_statement)
+        .collect_vec();
 
-    let table_names: HashSet<String> =
-        HashSet::from_iter(local_table_names.chain(remote_table_names));
+    let local_table_names: HashSet<String> =
+        HashSet::from_iter(local_definitions.iter().filter_map(map_table_name));
+
+    // 💡 ignore the tables managed by the migration tool, unless defined in the project
+    let history_table = config::retrieve_history_table(config_file);
+    let tool_table_names = [
+        get_event_table_name(&history_table),
+        MIGRATION_LOCK_TABLE_NAME.to_string(),
+        history_table,
+    ];
+    let remote_definitions = remote_definitions
+        .into_iter()
+        .filter(|definition| match map_table_name(definition) {
+            Some(table_name) => {
+                !tool_table_names.contains(&table_name) || local_table_names.contains(&table_name)
+            }
+            None => true,
+        })
+        .collect_vec();
 
-    let mut table_diffs = Vec::with_capacity(table_names.len());
+    let table_diffs = get_table_diffs(&local_definitions, &remote_definitions);
+    let database_diffs = get_database_diffs(&local_definitions, &remote_definitions);
+
+    let has_differences = !table_diffs.is_empty() || !database_diffs.is_empty();
+
+    match format {
+        DiffFormat::Text => match has_differences {
+            true => {
+                for table_diff in table_diffs
+                    .iter()
+                    .sorted_by(|a, b| natural_lexical_cmp(&a.name, &b.name))
+                {
+                    println!("{table_diff}");
+                }
+                for database_diff in database_diffs.iter() {
+                    println!("{database_diff}");
+                }
+            }
+            false => println!("No changes detected."),
+        },
+        DiffFormat::Json => {
+            let report = DiffReport::new(&table_diffs, &database_diffs);
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+
+    if let Some(name) = generate_migration {
+        if !has_differences {
+            println!("No migration generated.");
+            return Ok(DiffStatus::NoChanges);
+        }
+
+        let content =
+            migration::generate_migration_content(&table_diffs, &database_diffs, allow_destructive);
+
+        create::main(CreateArgs {
+            name,
+            operation: CreateOperation::Migration(CreateMigrationArgs {
+                down: false,
+                content: Some(content),
+            }),
+            config_file,
+        })?;
+    }
+
+    match has_differences {
+        true => Ok(DiffStatus::Changes),
+        false => Ok(DiffStatus::NoChanges),
+    }
+}
+
+fn get_table_diffs(
+    local_definitions: &[DefineStatement],
+    remote_definitions: &[DefineStatement],
+) -> Vec<TableDiff> {
+    let local_table_names: HashSet<String> =
+        HashSet::from_iter(local_definitions.iter().filter_map(map_table_name));
+    let remote_table_names: HashSet<String> =
+        HashSet::from_iter(remote_definitions.iter().filter_map(map_table_name));
+
+    let table_names = local_table_names.union(&remote_table_names);
+
+    let mut table_diffs = Vec::with_capacity(local_table_names.len());
 
     for table_name in table_names {
-        let remote_field_definitions = remote_statements
-            .iter()
-            .filter_map(map_define_field_statement);
-        let remote_field_definitions = remote_field_definitions.filter(|s| s.what.0 == table_name);
+        let local_table =
+            find_definition(local_definitions, DefinitionKind::Table, None, table_name);
+        let remote_table =
+            find_definition(remote_definitions, DefinitionKind::Table, None, table_name);
+
+        // 💡 a table can be implicitly defined by its fields
+        let is_implicit_local_table =
+            local_table.is_none() && local_table_names.contains(table_name);
+
+        let table = match is_implicit_local_table {
+            true => None,
+            false => DefinitionDiff::compare(
+                DefinitionKind::Table,
+                table_name.to_string(),
+                local_table.cloned(),
+                remote_table.cloned(),
+            ),
+        };
+
+        let definitions = [
+            DefinitionKind::Field,
+            DefinitionKind::Index,
+            DefinitionKind::Event,
+        ]
+        .into_iter()
+        .flat_map(|kind| {
+            get_definitions_diffs(
+                kind,
+                Some(table_name),
+                local_definitions,
+                remote_definitions,
+            )
+        })
+        // 💡 exclude certains fields automatically added by the server
+        .filter(|d| match (d.kind, &d.local) {
+            (DefinitionKind::Field, None) => {
+                !d.name.contains("[*]") && d.name != "in" && d.name != "out"
+            }
+            _ => true,
+        })
+        .collect_vec();
 
-        let local_field_definitions = local_statements
-            .iter()
-            .filter_map(map_define_field_statement);
-        let local_field_definitions = local_field_definitions.filter(|s| s.what.0 == table_name);
-
-        let local_fields: HashSet<String> =
-            HashSet::from_iter(local_field_definitions.clone().map(|s| s.name.to_string()));
-        let remote_fields: HashSet<String> =
-            HashSet::from_iter(remote_field_definitions.clone().map(|s| s.name.to_string()));
-
-        let only_local_fields = local_fields.difference(&remote_fields);
-        let only_remote_fields = remote_fields
-            .difference(&local_fields)
-            // 💡 exclude certains fields automatically added by the server
-            .filter(|f| !f.contains("[*]"))
-            .filter(|f| f != &"in")
-            .filter(|f| f != &"out");
-
-        let additions = HashSet::from_iter(only_local_fields.cloned());
-        let deletions = HashSet::from_iter(only_remote_fields.cloned());
-
-        let changes = remote_field_definitions.filter(|remote_d| {
-            let field_name = remote_d.name.to_string();
-            let local_d = local_field_definitions
-                .clone()
-                .find(|ld| ld.name.to_string() == field_name);
-
-            let Some(local_d) = local_d else {
-                return false;
-            };
-
-            remote_d.readonly != local_d.readonly
-                || remote_d.assert != local_d.assert
-                || remote_d.default != local_d.default
-                || remote_d.kind != local_d.kind
-                || remote_d.permissions != local_d.permissions
-                || remote_d.reference != local_d.reference
-        });
-        let changes = HashSet::from_iter(changes.map(|s| s.name.to_string()));
-
-        if !additions.is_empty() || !changes.is_empty() || !deletions.is_empty() {
-            let table_diff = TableDiff {
-                name: table_name.to_string(),
-                additions,
-                changes,
-                deletions,
-            };
+        let table_diff = TableDiff {
+            name: table_name.to_string(),
+            table,
+            definitions,
+        };
+
+        if !table_diff.is_empty() {
             table_diffs.push(table_diff);
         }
     }
 
-    if table_diffs.is_empty() {
-        println!("No changes detected.");
-    } else {
-        for table_diff in table_diffs
+    table_diffs
+}
+
+fn get_database_diffs(
+    local_definitions: &[DefineStatement],
+    remote_definitions: &[DefineStatement],
+) -> Vec<DatabaseDiff> {
+    [
+        DefinitionKind::Analyzer,
+        DefinitionKind::Function,
+        DefinitionKind::Param,
+        DefinitionKind::Access,
+    ]
+    .into_iter()
+    .map(|kind| DatabaseDiff {
+        kind,
+        definitions: get_definitions_diffs(kind, None, local_definitions, remote_definitions),
+    })
+    .filter(|database_diff| !database_diff.definitions.is_empty())
+    .collect()
+}
+
+fn get_definitions_diffs(
+    kind: DefinitionKind,
+    table_name: Option<&String>,
+    local_definitions: &[DefineStatement],
+    remote_definitions: &[DefineStatement],
+) -> Vec<DefinitionDiff> {
+    let get_names = |definitions: &[DefineStatement]| {
+        definitions
             .iter()
-            .sorted_by(|a, b| natural_lexical_cmp(&a.name, &b.name))
-        {
-            println!("{table_diff}");
-        }
+            .filter(|d| is_definition_of(d, kind, table_name))
+            .map(|d| get_definition_key(d).name)
+            .collect::<HashSet<_>>()
+    };
+
+    let local_names = get_names(local_definitions);
+    let remote_names = get_names(remote_definitions);
+
+    local_names
+        .union(&remote_names)
+        .filter_map(|name| {
+            let local_definition = find_definition(local_definitions, kind, table_name, name);
+            let remote_definition = find_definition(remote_definitions, kind, table_name, name);
+
+            DefinitionDiff::compare(
+                kind,
+                name.to_string(),
+                local_definition.cloned(),
+                remote_definition.cloned(),
+            )
+        })
+        .collect()
+}
+
+fn find_definition<'a>(
+    definitions: &'a [DefineStatement],
+    kind: DefinitionKind,
+    table_name: Option<&String>,
+    name: &str,
+) -> Option<&'a DefineStatement> {
+    definitions
+        .iter()
+        .filter(|d| is_definition_of(d, kind, table_name))
+        .rfind(|d| get_definition_key(d).name == name)
+}
+
+fn is_definition_of(
+    definition: &DefineStatement,
+    kind: DefinitionKind,
+    table_name: Option<&String>,
+) -> bool {
+    if DefinitionKind::from_definition(definition) != Some(kind) {
+        return false;
     }
 
-    Ok(())
+    match (kind, table_name) {
+        (DefinitionKind::Field | DefinitionKind::Index | DefinitionKind::Event, table_name) => {
+            table_name.is_some() && get_definition_key(definition).scope.as_ref() == table_name
+        }
+        // 💡 only the access methods of the database are compared
+        (DefinitionKind::Access, _) => match definition {
+            DefineStatement::Access(access) => matches!(access.base, Base::Db),
+            _ => false,
+        },
+        _ => true,
+    }
 }
 
-fn get_local_statements(config_file: Option<&Path>) -> Result<Vec<::surrealdb::sql::Statement>> {
+fn get_local_statements(config_file: Option<&Path>) -> Result<Vec<Statement>> {
     let tags = HashSet::from([ALL_TAGS.into()]);
     let exclude_tags = HashSet::new();
 
-    let schemas_files = io::extract_schemas_files(config_file, None, &tags, &exclude_tags)
+    let source = FileSystemSource::from_config_file(config_file);
+
+    let schemas_files = io::extract_schemas_files(&source, &tags, &exclude_tags)
         .ok()
         .unwrap_or_default();
-    let events_files = io::extract_events_files(config_file, None, &tags, &exclude_tags)
+    let events_files = io::extract_events_files(&source, &tags, &exclude_tags)
         .ok()
         .unwrap_or_default();
 
     let schema_definitions = io::concat_files_content(&schemas_files);
     let event_definitions = io::concat_files_content(&events_files);
 
-    let forward_migrations_files = io::extract_migrations_files(
-        config_file,
-        None,
-        MigrationDirection::Forward,
-        &tags,
-        &exclude_tags,
-    );
+    let forward_migrations_files =
+        io::extract_migrations_files(&source, MigrationDirection::Forward, &tags, &exclude_tags);
 
     let use_traditional_approach = schema_definitions.is_empty()
         && event_definitions.is_empty()
@@ -144,6 +339,7 @@ fn get_local_statements(config_file: Option<&Path>) -> Result<Vec<::surrealdb::s
         use_traditional_approach,
         &schemas_files,
         &forward_migrations_files,
+        &config::retrieve_history_table(config_file),
     )?;
 
     let use_traditional_approach = schema_definitions.is_empty()
@@ -151,7 +347,15 @@ fn get_local_statements(config_file: Option<&Path>) -> Result<Vec<::surrealdb::s
         && !forward_migrations_files.is_empty();
 
     let local_statements = if use_traditional_approach {
-        todo!("Extracting schema definition in traditional approach is not yet available")
+        let migrations_statements = forward_migrations_files
+            .iter()
+            .map(|migration_file| {
+                let content = migration_file.get_content().unwrap_or_default();
+                parse_file_statements(&migration_file.name, &content)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        traditional::fold_migrations_statements(migrations_statements.into_iter().flatten())
     } else {
         let schemas_statements = parse_statements(&schema_definitions)?;
         let events_statements = parse_statements(&event_definitions)?;
@@ -165,24 +369,32 @@ fn get_local_statements(config_file: Option<&Path>) -> Result<Vec<::surrealdb::s
     Ok(local_statements)
 }
 
-async fn get_remote_statements(
-    config_file: Option<&Path>,
-) -> Result<Vec<::surrealdb::sql::Statement>> {
+async fn get_remote_statements(config_file: Option<&Path>) -> Result<Vec<Statement>> {
     let client = create_surrealdb_client(config_file, &SurrealdbConfiguration::default()).await?;
 
-    let table_definitions = get_surrealdb_table_definitions(&client).await?;
+    let database_definition = get_surrealdb_database_definition(&client).await?;
 
-    let futures = table_definitions
+    let futures = database_definition
+        .tables
         .keys()
         .map(|table_name| get_surrealdb_table_definition(&client, table_name));
 
     let inner_table_definitions: Result<Vec<_>, _> = join_all(futures).await.into_iter().collect();
     let inner_table_definitions = inner_table_definitions?;
 
-    let remote_statements = inner_table_definitions
-        .into_iter()
-        .flat_map(|d| d.fields.values().cloned().collect_vec())
-        .chain(table_definitions.clone().into_values());
+    let remote_statements = database_definition
+        .analyzers
+        .into_values()
+        .chain(database_definition.functions.into_values())
+        .chain(database_definition.params.into_values())
+        .chain(database_definition.accesses.into_values())
+        .chain(database_definition.tables.into_values())
+        .chain(inner_table_definitions.into_iter().flat_map(|d| {
+            d.fields
+                .into_values()
+                .chain(d.indexes.into_values())
+                .chain(d.events.into_values())
+        }));
     let remote_statements: Result<Vec<_>, _> =
         remote_statements.map(|s| parse_statements(&s)).collect();
     let remote_statements = remote_statements?;
@@ -194,28 +406,182 @@ async fn get_remote_statements(
     Ok(remote_statements)
 }
 
-fn map_table_name(statement: &::surrealdb::sql::Statement) -> Option<String> {
+fn map_define_statement(statement: Statement) -> Option<DefineStatement> {
     match statement {
-        ::surrealdb::sql::Statement::Define(define_statement) => match define_statement {
-            ::surrealdb::sql::statements::DefineStatement::Table(statement) => {
-                Some(statement.name.0.to_string())
-            }
-            ::surrealdb::sql::statements::DefineStatement::Field(statement) => {
-                Some(statement.what.0.to_string())
-            }
-            _ => None,
-        },
+        Statement::Define(define_statement) => Some(define_statement),
         _ => None,
     }
 }
 
-fn map_define_field_statement(
-    statement: &::surrealdb::sql::Statement,
-) -> Option<::surrealdb::sql::statements::DefineFieldStatement> {
-    match statement {
-        ::surrealdb::sql::Statement::Define(
-            ::surrealdb::sql::statements::DefineStatement::Field(define_field_statement),
-        ) => Some(define_field_statement.clone()),
+fn map_table_name(definition: &DefineStatement) -> Option<String> {
+    match definition {
+        DefineStatement::Table(statement) => Some(statement.name.0.to_string()),
+        DefineStatement::Field(statement) => Some(statement.what.0.to_string()),
+        DefineStatement::Index(statement) => Some(statement.what.0.to_string()),
+        DefineStatement::Event(statement) => Some(statement.what.0.to_string()),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_definitions(content: &str) -> Vec<DefineStatement> {
+        parse_statements(content)
+            .unwrap()
+            .into_iter()
+            .filter_map(map_define_statement)
+            .collect()
+    }
+
+    #[test]
+    fn detect_table_indexes_and_events_changes() {
+        let local_definitions = parse_definitions(
+            "DEFINE TABLE post SCHEMAFULL PERMISSIONS FOR select FULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE INDEX title_index ON post FIELDS title UNIQUE;",
+        );
+        let remote_definitions = parse_definitions(
+            "DEFINE TABLE post SCHEMALESS;
+DEFINE FIELD title ON post TYPE string;
+DEFINE EVENT post_created ON TABLE post WHEN $event == 'CREATE' THEN {};",
+        );
+
+        let table_diffs = get_table_diffs(&local_definitions, &remote_definitions);
+
+        assert_eq!(table_diffs.len(), 1);
+        assert_eq!(
+            table_diffs[0].to_string(),
+            "~post (1 additions, 1 changes, 1 deletions)
+  ~table options: schema, type, permissions
+  +index title_index
+  -event post_created
+"
+        );
+    }
+
+    #[test]
+    fn detect_database_definitions_changes() {
+        let local_definitions = parse_definitions(
+            "DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello ' + $name; };
+DEFINE PARAM $limit VALUE 20;
+DEFINE ANALYZER simple TOKENIZERS blank;",
+        );
+        let remote_definitions = parse_definitions(
+            "DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello ' + $name; };
+DEFINE PARAM $limit VALUE 10;",
+        );
+
+        let database_diffs = get_database_diffs(&local_definitions, &remote_definitions)
+            .iter()
+            .map(|database_diff| database_diff.to_string())
+            .collect_vec();
+
+        assert_eq!(
+            database_diffs,
+            vec![
+                "+analyzers (1 additions)\n  +simple\n",
+                "~params (1 changes)\n  ~$limit\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_migration_from_differences() {
+        let local_definitions = parse_definitions(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE FIELD IF NOT EXISTS content ON post TYPE string;",
+        );
+        let remote_definitions = parse_definitions(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE option<string>;
+DEFINE FIELD author ON post TYPE string;
+DEFINE TABLE comment SCHEMALESS;
+DEFINE FIELD content ON comment TYPE string;
+DEFINE PARAM $limit VALUE 10;",
+        );
+
+        let table_diffs = get_table_diffs(&local_definitions, &remote_definitions);
+        let database_diffs = get_database_diffs(&local_definitions, &remote_definitions);
+
+        assert_eq!(
+            migration::generate_migration_content(&table_diffs, &database_diffs, false),
+            "DEFINE FIELD OVERWRITE content ON post TYPE string PERMISSIONS FULL;
+DEFINE FIELD OVERWRITE title ON post TYPE string PERMISSIONS FULL;
+
+# Destructive statements are commented out, uncomment them to apply
+# REMOVE FIELD author ON post;
+# REMOVE TABLE comment;
+# REMOVE PARAM $limit;"
+        );
+    }
+
+    #[test]
+    fn report_definitions_before_and_after() {
+        let local_definitions = parse_definitions(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;",
+        );
+        let remote_definitions = parse_definitions(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE option<string>;
+DEFINE PARAM $limit VALUE 10;",
+        );
+
+        let table_diffs = get_table_diffs(&local_definitions, &remote_definitions);
+        let database_diffs = get_database_diffs(&local_definitions, &remote_definitions);
+
+        let report = DiffReport::new(&table_diffs, &database_diffs);
+
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "tables": [
+                    {
+                        "name": "post",
+                        "additions": [],
+                        "changes": [
+                            {
+                                "kind": "field",
+                                "name": "title",
+                                "before": "DEFINE FIELD title ON post TYPE option<string> PERMISSIONS FULL",
+                                "after": "DEFINE FIELD title ON post TYPE string PERMISSIONS FULL",
+                            }
+                        ],
+                        "deletions": [],
+                    }
+                ],
+                "database": {
+                    "additions": [],
+                    "changes": [],
+                    "deletions": [
+                        {
+                            "kind": "param",
+                            "name": "$limit",
+                            "before": "DEFINE PARAM $limit VALUE 10 PERMISSIONS FULL",
+                            "after": null,
+                        }
+                    ],
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn ignore_identical_definitions() {
+        let definitions = parse_definitions(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE INDEX title_index ON post FIELDS title;",
+        );
+        let overwritten_definitions = parse_definitions(
+            "DEFINE TABLE OVERWRITE post SCHEMAFULL;
+DEFINE FIELD OVERWRITE title ON post TYPE string;
+DEFINE INDEX OVERWRITE title_index ON post FIELDS title;",
+        );
+
+        assert!(get_table_diffs(&definitions, &overwritten_definitions).is_empty());
+    }
+}
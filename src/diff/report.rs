@@ -0,0 +1,99 @@
+use ::surrealdb::sql::statements::DefineStatement;
+use itertools::Itertools;
+use lexicmp::natural_lexical_cmp;
+use serde::Serialize;
+
+use super::{
+    database_diff::DatabaseDiff,
+    definition_diff::{DefinitionDiff, compare_definitions},
+    diff_symbol::DiffSymbol,
+    table_diff::TableDiff,
+};
+use crate::rollback::normalize;
+
+/// A machine-readable report of the differences, e.g. to detect drift in a CI pipeline.
+#[derive(Serialize)]
+pub struct DiffReport {
+    pub tables: Vec<TableDiffReport>,
+    pub database: DefinitionsDiffReport,
+}
+
+#[derive(Serialize)]
+pub struct TableDiffReport {
+    pub name: String,
+    #[serde(flatten)]
+    pub definitions: DefinitionsDiffReport,
+}
+
+#[derive(Serialize, Default)]
+pub struct DefinitionsDiffReport {
+    pub additions: Vec<DefinitionDiffReport>,
+    pub changes: Vec<DefinitionDiffReport>,
+    pub deletions: Vec<DefinitionDiffReport>,
+}
+
+#[derive(Serialize)]
+pub struct DefinitionDiffReport {
+    pub kind: &'static str,
+    pub name: String,
+    /// The definition found in the remote database.
+    pub before: Option<String>,
+    /// The definition expected by the local project.
+    pub after: Option<String>,
+}
+
+impl DiffReport {
+    pub fn new(table_diffs: &[TableDiff], database_diffs: &[DatabaseDiff]) -> Self {
+        let tables = table_diffs
+            .iter()
+            .sorted_by(|a, b| natural_lexical_cmp(&a.name, &b.name))
+            .map(|table_diff| TableDiffReport {
+                name: table_diff.name.to_string(),
+                definitions: DefinitionsDiffReport::new(
+                    table_diff.table.iter().chain(table_diff.definitions.iter()),
+                ),
+            })
+            .collect();
+
+        let database = DefinitionsDiffReport::new(
+            database_diffs
+                .iter()
+                .flat_map(|database_diff| database_diff.definitions.iter()),
+        );
+
+        DiffReport { tables, database }
+    }
+}
+
+impl DefinitionsDiffReport {
+    fn new<'a>(definitions: impl Iterator<Item = &'a DefinitionDiff>) -> Self {
+        let mut report = DefinitionsDiffReport::default();
+
+        for definition in definitions.sorted_by(|a, b| compare_definitions(a, b)) {
+            let definition_report = DefinitionDiffReport {
+                kind: definition.kind.as_str(),
+                name: definition.display_name(),
+                before: definition.remote.as_ref().map(to_report_definition),
+                after: definition.local.as_ref().map(to_report_definition),
+            };
+
+            match definition.symbol() {
+                DiffSymbol::Addition => report.additions.push(definition_report),
+                DiffSymbol::Change => report.changes.push(definition_report),
+                DiffSymbol::Deletion => report.deletions.push(definition_report),
+            }
+        }
+
+        report
+    }
+}
+
+fn to_report_definition(definition: &DefineStatement) -> String {
+    // 💡 never leak the secrets of access methods
+    let definition = match definition {
+        DefineStatement::Access(access) => DefineStatement::Access(access.redacted()),
+        definition => definition.clone(),
+    };
+
+    normalize(&definition).to_string()
+}
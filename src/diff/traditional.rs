@@ -0,0 +1,142 @@
+use ::surrealdb::sql::{
+    Statement,
+    statements::{DefineStatement, RemoveStatement},
+};
+
+use crate::rollback::{DefinitionKey, get_definition_key};
+
+/// Reconstruct the schema of a traditional project by folding the DEFINE and REMOVE statements
+/// of all forward migrations, in order. Other statements (e.g. data changes) are ignored.
+pub fn fold_migrations_statements(
+    statements: impl IntoIterator<Item = Statement>,
+) -> Vec<Statement> {
+    let mut definitions: Vec<(DefinitionKey, DefineStatement)> = Vec::new();
+
+    for statement in statements {
+        match statement {
+            Statement::Define(definition) => {
+                let key = get_definition_key(&definition);
+                let existing_definition = definitions
+                    .iter_mut()
+                    .find(|(existing_key, _)| existing_key == &key);
+
+                match existing_definition {
+                    Some(_) if is_if_not_exists(&definition) => {}
+                    Some((_, existing_definition)) => *existing_definition = definition,
+                    None => definitions.push((key, definition)),
+                }
+            }
+            Statement::Remove(remove_statement) => {
+                let Some(key) = get_removed_definition_key(&remove_statement) else {
+                    continue;
+                };
+
+                definitions.retain(|(existing_key, _)| {
+                    let is_removed = existing_key == &key;
+                    let is_in_removed_table = key.kind == "table"
+                        && matches!(existing_key.kind, "field" | "index" | "event")
+                        && existing_key.scope.as_ref() == Some(&key.name);
+
+                    !is_removed && !is_in_removed_table
+                });
+            }
+            _ => {}
+        }
+    }
+
+    definitions
+        .into_iter()
+        .map(|(_, definition)| Statement::Define(definition))
+        .collect()
+}
+
+fn is_if_not_exists(definition: &DefineStatement) -> bool {
+    match definition {
+        DefineStatement::Namespace(s) => s.if_not_exists,
+        DefineStatement::Database(s) => s.if_not_exists,
+        DefineStatement::Function(s) => s.if_not_exists,
+        DefineStatement::Analyzer(s) => s.if_not_exists,
+        DefineStatement::Param(s) => s.if_not_exists,
+        DefineStatement::Table(s) => s.if_not_exists,
+        DefineStatement::Event(s) => s.if_not_exists,
+        DefineStatement::Field(s) => s.if_not_exists,
+        DefineStatement::Index(s) => s.if_not_exists,
+        DefineStatement::User(s) => s.if_not_exists,
+        DefineStatement::Model(s) => s.if_not_exists,
+        DefineStatement::Access(s) => s.if_not_exists,
+        DefineStatement::Config(s) => s.if_not_exists,
+        DefineStatement::Api(s) => s.if_not_exists,
+        _ => false,
+    }
+}
+
+fn get_removed_definition_key(statement: &RemoveStatement) -> Option<DefinitionKey> {
+    let (kind, scope, name) = match statement {
+        RemoveStatement::Namespace(s) => ("namespace", None, s.name.to_string()),
+        RemoveStatement::Database(s) => ("database", None, s.name.to_string()),
+        RemoveStatement::Function(s) => ("function", None, s.name.to_string()),
+        RemoveStatement::Analyzer(s) => ("analyzer", None, s.name.to_string()),
+        RemoveStatement::Param(s) => ("param", None, s.name.to_string()),
+        RemoveStatement::Table(s) => ("table", None, s.name.to_string()),
+        RemoveStatement::Event(s) => ("event", Some(s.what.to_string()), s.name.to_string()),
+        RemoveStatement::Field(s) => ("field", Some(s.what.to_string()), s.name.to_string()),
+        RemoveStatement::Index(s) => ("index", Some(s.what.to_string()), s.name.to_string()),
+        RemoveStatement::User(s) => ("user", Some(s.base.to_string()), s.name.to_string()),
+        RemoveStatement::Model(s) => ("model", Some(s.version.clone()), s.name.to_string()),
+        RemoveStatement::Access(s) => ("access", Some(s.base.to_string()), s.name.to_string()),
+        _ => return None,
+    };
+
+    Some(DefinitionKey { kind, scope, name })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surrealdb::parse_statements;
+
+    fn fold(content: &str) -> Vec<String> {
+        let statements = parse_statements(content).unwrap();
+
+        fold_migrations_statements(statements)
+            .iter()
+            .map(|statement| statement.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn keep_latest_definitions() {
+        let statements = fold(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+CREATE post SET title = 'Hello';
+DEFINE FIELD OVERWRITE title ON post TYPE option<string>;
+DEFINE FIELD IF NOT EXISTS title ON post TYPE int;",
+        );
+
+        assert_eq!(
+            statements,
+            vec![
+                "DEFINE TABLE post TYPE NORMAL SCHEMAFULL PERMISSIONS NONE",
+                "DEFINE FIELD OVERWRITE title ON post TYPE option<string> PERMISSIONS FULL",
+            ]
+        );
+    }
+
+    #[test]
+    fn forget_removed_definitions() {
+        let statements = fold(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE TABLE comment SCHEMALESS;
+DEFINE PARAM $limit VALUE 10;
+REMOVE TABLE post;
+REMOVE PARAM $limit;",
+        );
+
+        assert_eq!(
+            statements,
+            vec!["DEFINE TABLE comment TYPE ANY SCHEMALESS PERMISSIONS NONE"]
+        );
+    }
+}
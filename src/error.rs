@@ -0,0 +1,152 @@
+use color_eyre::eyre::Report;
+use std::fmt::{Display, Formatter};
+
+/// The errors that can be returned by the library.
+///
+/// Known failures are exposed as dedicated variants so that they can be handled programmatically,
+/// any other failure is wrapped in the `Other` variant.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use color_eyre::eyre::Result;
+/// use surrealdb_migrations::{MigrationError, MigrationRunner};
+/// use surrealdb::engine::any::connect;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let db = connect("ws://localhost:8000").await?;
+///
+/// match MigrationRunner::new(&db).validate_checksum().await {
+///     Ok(()) => {}
+///     Err(MigrationError::ChecksumMismatch { migration }) => {
+///         eprintln!("Migration '{migration}' has been modified after being applied");
+///     }
+///     Err(error) => return Err(error.into()),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The content of an applied migration file changed since it was applied.
+    ChecksumMismatch { migration: String },
+    /// A migration is recorded as applied but its file does not exist anymore.
+    MissingMigrationFile { migration: String },
+    /// The requested migration does not match any migration file.
+    UnknownMigration { migration: String },
+    /// The requested migration has not been applied yet.
+    MigrationNotApplied {
+        #[allow(dead_code)] // only read by library consumers, not by the CLI
+        migration: String,
+    },
+    /// Some migrations older than the last applied migration have not been applied.
+    /// Use the out-of-order mode to apply them.
+    VersionOrderGap { migrations: Vec<String> },
+    /// A migration file contains invalid SurrealQL.
+    ParseError {
+        file: String,
+        #[allow(dead_code)] // only read by library consumers, not by the CLI
+        line: Option<usize>,
+        message: String,
+    },
+    /// The transaction of a migration has been rejected by the database.
+    TransactionFailed {
+        #[allow(dead_code)] // only read by library consumers, not by the CLI
+        migration: Option<String>,
+        errors: Vec<String>,
+    },
+    /// A non-transactional migration (`.notx.surql`) failed, its changes cannot be rolled back.
+    NonTransactionalMigrationFailed {
+        migration: String,
+        errors: Vec<String>,
+    },
+    /// A migration definition file cannot be applied on the previous definition.
+    DefinitionPatchFailed { message: String },
+    /// The embedded migration definitions do not match the schema and event files.
+    OutdatedDefinitions,
+    /// The migration lock is still held by another migration run after the wait timeout.
+    LockTimeout { owner: String, expires_at: String },
+    /// The lease of the migration lock expired before it could be renewed and another migration run took it.
+    LockLost,
+    /// A value of the configuration file (or of its environment variable) is invalid.
+    InvalidConfigValue { key: String, value: String },
+    /// Any other error.
+    Other(Report),
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::ChecksumMismatch { migration } => {
+                write!(
+                    f,
+                    "The checksum does not match for migration '{migration}'."
+                )
+            }
+            MigrationError::MissingMigrationFile { migration } => {
+                write!(f, "The migration file '{migration}' does not exist.")
+            }
+            MigrationError::UnknownMigration { migration } => {
+                write!(f, "The migration file '{migration}' was not found")
+            }
+            MigrationError::MigrationNotApplied { .. } => write!(
+                f,
+                "This migration was not applied in the SurrealDB instance. Please make sure you correctly applied this migration before."
+            ),
+            MigrationError::VersionOrderGap { migrations } => write!(
+                f,
+                "The following migrations have not been applied: {}. Use the out-of-order mode to apply them.",
+                migrations.join(", ")
+            ),
+            MigrationError::ParseError { file, message, .. } => {
+                write!(f, "Failed to parse migration '{file}': {message}")
+            }
+            MigrationError::TransactionFailed { errors, .. } => {
+                write!(f, "{}", errors.join("\n"))
+            }
+            MigrationError::NonTransactionalMigrationFailed { migration, errors } => write!(
+                f,
+                "The migration '{migration}' was executed outside of a transaction and failed, its changes cannot be rolled back and the database may be partially migrated.\n{}",
+                errors.join("\n")
+            ),
+            MigrationError::DefinitionPatchFailed { message } => {
+                write!(f, "Failed to apply migration definition patch: {message}")
+            }
+            MigrationError::OutdatedDefinitions => write!(
+                f,
+                "The migration definitions are not up to date. Please run `surrealdb-migrations apply` on your local environment and publish definitions files."
+            ),
+            MigrationError::LockTimeout { owner, expires_at } => write!(
+                f,
+                "The migration lock is held by another migration run ('{owner}') until {expires_at}. Please try again later."
+            ),
+            MigrationError::LockLost => write!(
+                f,
+                "The migration lock has been taken by another migration run after its lease expired."
+            ),
+            MigrationError::InvalidConfigValue { key, value } => {
+                write!(f, "Invalid value '{value}' for the '{key}' configuration.")
+            }
+            MigrationError::Other(report) => write!(f, "{report}"),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrationError::Other(report) => Some(report.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<Report> for MigrationError {
+    fn from(report: Report) -> Self {
+        match report.downcast::<MigrationError>() {
+            Ok(error) => error,
+            Err(report) => MigrationError::Other(report),
+        }
+    }
+}
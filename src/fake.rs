@@ -0,0 +1,176 @@
+use ::surrealdb::{Connection, Surreal};
+use color_eyre::eyre::{Result, eyre};
+use std::collections::HashSet;
+
+use crate::{
+    code_migration::{
+        NamedCodeMigration, ensure_code_migrations_names_are_unique, extract_code_migrations_files,
+        find_code_migration,
+    },
+    constants::ALL_TAGS,
+    error::MigrationError,
+    io,
+    lock::{self, LockOptions},
+    migration_event::{NewMigrationEvent, record_migration_events},
+    models::{ChecksumAlgorithm, MigrationDirection, MigrationEventAction},
+    script_migration::{
+        HistoryTableSetup, RunMetadata, create_script_migration_statement,
+        delete_script_migration_statement, prepare_history_table,
+    },
+    source::MigrationSource,
+    surrealdb::{self, TransactionAction},
+};
+
+pub enum FakeOperation {
+    /// Record the migration as applied, without executing it.
+    Fake,
+    /// Remove the migration from the history, without reverting it.
+    Unfake,
+}
+
+pub struct FakeArgs<'a, C: Connection> {
+    pub operation: FakeOperation,
+    pub migration_name: String,
+    pub db: &'a Surreal<C>,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
+    pub code_migrations: &'a [NamedCodeMigration<'a, C>],
+    pub checksum_algorithm: ChecksumAlgorithm,
+    pub executor: Option<String>,
+    pub lock: Option<LockOptions>,
+}
+
+/// Creates or deletes the history record of a migration, without executing the migration.
+/// Returns the name of the migration faked.
+pub async fn main<C: Connection>(args: FakeArgs<'_, C>) -> Result<String> {
+    let client = args.db;
+    let history_table = args.history_table;
+    let lock = args.lock;
+
+    lock::run_with_lock(client, history_table, lock, fake(args)).await
+}
+
+async fn fake<C: Connection>(args: FakeArgs<'_, C>) -> Result<String> {
+    let FakeArgs {
+        operation,
+        migration_name,
+        db: client,
+        source,
+        history_table,
+        code_migrations,
+        checksum_algorithm,
+        executor,
+        lock: _,
+    } = args;
+
+    let tags = HashSet::from([ALL_TAGS.into()]);
+    let exclude_tags = HashSet::new();
+
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags);
+
+    ensure_code_migrations_names_are_unique(code_migrations, &forward_migrations_files)?;
+
+    let forward_migrations_files = forward_migrations_files
+        .into_iter()
+        .chain(extract_code_migrations_files(
+            code_migrations,
+            MigrationDirection::Forward,
+            &tags,
+            &exclude_tags,
+        ))
+        .collect::<Vec<_>>();
+
+    let Some(migration_file) = forward_migrations_files
+        .iter()
+        .find(|f| f.name == migration_name || f.full_name == migration_name)
+    else {
+        return Err(MigrationError::UnknownMigration {
+            migration: migration_name,
+        }
+        .into());
+    };
+
+    let migrations_applied =
+        surrealdb::list_script_migration_ordered_by_execution_date(client, history_table).await?;
+
+    let is_migration_applied = migrations_applied
+        .iter()
+        .any(|migration_applied| migration_applied.script_name == migration_file.name);
+
+    let run_metadata = RunMetadata::new(executor, &tags);
+
+    let statements = match operation {
+        FakeOperation::Fake => {
+            if is_migration_applied {
+                return Err(eyre!(
+                    "The migration '{}' is already applied.",
+                    migration_file.name
+                ));
+            }
+
+            let HistoryTableSetup {
+                mut statements,
+                checksum_support,
+            } = prepare_history_table(
+                client,
+                source,
+                history_table,
+                &[migration_file],
+                &tags,
+                &exclude_tags,
+            )
+            .await?;
+
+            let is_code_migration =
+                find_code_migration(code_migrations, &migration_file.name).is_some();
+
+            let checksum = match is_code_migration {
+                true => None,
+                false => checksum_support.compute(
+                    &migration_file.get_content().unwrap_or_default(),
+                    checksum_algorithm,
+                )?,
+            };
+
+            statements.push(create_script_migration_statement(
+                history_table,
+                &migration_file.name,
+                checksum.as_ref(),
+                &run_metadata,
+                None,
+            ));
+
+            statements
+        }
+        FakeOperation::Unfake => {
+            if !is_migration_applied {
+                return Err(MigrationError::MigrationNotApplied {
+                    migration: migration_file.name.to_string(),
+                }
+                .into());
+            }
+
+            vec![delete_script_migration_statement(
+                history_table,
+                &migration_file.name,
+            )]
+        }
+    };
+
+    let result =
+        surrealdb::apply_in_transaction(client, statements, TransactionAction::Commit, None).await;
+
+    let action = match operation {
+        FakeOperation::Fake => MigrationEventAction::Fake,
+        FakeOperation::Unfake => MigrationEventAction::Unfake,
+    };
+    let event = NewMigrationEvent::from_result(action, &migration_file.name, &result);
+    let events_recorded =
+        record_migration_events(client, history_table, &run_metadata, vec![event]).await;
+
+    result?;
+    events_recorded?;
+
+    Ok(migration_file.name.to_string())
+}
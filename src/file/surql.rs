@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::constants::{ALL_TAGS, DOWN_TAG};
+use crate::constants::{ALL_TAGS, DOWN_TAG, NO_TRANSACTION_TAG};
 
 pub struct SurqlFile {
     pub name: String,
@@ -18,6 +18,10 @@ impl SurqlFile {
         self.tags.contains(DOWN_TAG)
     }
 
+    pub fn is_non_transactional(&self) -> bool {
+        self.tags.contains(NO_TRANSACTION_TAG)
+    }
+
     pub fn filter_by_tags(
         &self,
         filter_tags: &HashSet<String>,
@@ -52,6 +56,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn non_transactional_if_notx_tag() {
+        let file = create_surql_file(vec!["root", "notx"]);
+
+        assert!(file.is_non_transactional());
+    }
+
     #[test]
     fn always_true_if_filter_all_tags() {
         let filter_tags = HashSet::from([ALL_TAGS.into()]);
@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use crate::{cli, input::SurrealdbConfiguration};
+
+pub struct HistoryArgs<'a> {
+    pub db_configuration: SurrealdbConfiguration,
+    pub no_color: bool,
+    pub config_file: Option<&'a Path>,
+}
+
+impl<'a> HistoryArgs<'a> {
+    pub fn from(value: cli::HistoryArgs, config_file: Option<&'a Path>) -> Self {
+        let cli::HistoryArgs {
+            address,
+            ns,
+            db,
+            username,
+            password,
+            no_color,
+        } = value;
+
+        let db_configuration = SurrealdbConfiguration {
+            address,
+            ns,
+            db,
+            username,
+            password,
+        };
+
+        HistoryArgs {
+            db_configuration,
+            no_color,
+            config_file,
+        }
+    }
+}
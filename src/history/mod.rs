@@ -0,0 +1,73 @@
+pub mod args;
+
+pub use args::HistoryArgs;
+use cli_table::{Cell, ColorChoice, Style, Table, format::Border};
+use color_eyre::eyre::Result;
+
+use crate::{
+    common::get_migration_display_name, config, migration_event::list_migration_events,
+    runbin::surrealdb::create_surrealdb_client,
+};
+
+const NOT_AVAILABLE: &str = "N/A";
+
+pub async fn main(args: HistoryArgs<'_>) -> Result<()> {
+    let HistoryArgs {
+        db_configuration,
+        no_color,
+        config_file,
+    } = args;
+
+    let client = create_surrealdb_client(config_file, &db_configuration).await?;
+
+    let history_table = config::retrieve_history_table(config_file);
+
+    let events = list_migration_events(&client, &history_table).await?;
+
+    if events.is_empty() {
+        println!("No migration events recorded yet!");
+    } else {
+        let rows = events
+            .iter()
+            .map(|event| {
+                let display_name = get_migration_display_name(&event.script_name);
+                let executed_by = event.executed_by.as_deref().unwrap_or(NOT_AVAILABLE);
+                let error = event.error.as_deref().unwrap_or(NOT_AVAILABLE);
+
+                vec![
+                    event.executed_at.as_str().cell(),
+                    event.action.as_str().cell(),
+                    display_name.cell(),
+                    event.outcome.as_str().cell(),
+                    executed_by.cell(),
+                    error.cell(),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let color_choice = if no_color {
+            ColorChoice::Never
+        } else {
+            ColorChoice::Auto
+        };
+
+        let table = rows
+            .table()
+            .title(vec![
+                "Executed at".cell().bold(true),
+                "Action".cell().bold(true),
+                "Migration".cell().bold(true),
+                "Outcome".cell().bold(true),
+                "Executed by".cell().bold(true),
+                "Error".cell().bold(true),
+            ])
+            .color_choice(color_choice)
+            .border(Border::builder().build());
+
+        let table_display = table.display()?;
+
+        println!("{table_display}");
+    }
+
+    Ok(())
+}
@@ -1,40 +1,32 @@
 use ::surrealdb::sql::{Query, Statement, statements::DefineStatement};
-use color_eyre::eyre::{ContextCompat, Result, WrapErr, eyre};
-use fs_extra::dir::{DirEntryAttr, DirEntryValue};
-use include_dir::Dir;
+use color_eyre::eyre::{ContextCompat, Result, eyre};
 use itertools::Itertools;
 use lexicmp::natural_lexical_cmp;
 use std::{
     cmp::Ordering,
-    collections::{HashMap, HashSet},
+    collections::HashSet,
     path::{Path, PathBuf},
 };
 
 use crate::{
-    config,
     constants::{
-        DOWN_MIGRATIONS_DIR_NAME, DOWN_SURQL_FILE_EXTENSION, DOWN_TAG, EVENTS_DIR_NAME,
-        JSON_FILE_EXTENSION, MIGRATIONS_DIR_NAME, ROOT_TAG, SCHEMAS_DIR_NAME,
-        SCRIPT_MIGRATION_TABLE_NAME, SURQL_FILE_EXTENSION,
+        DEFINITIONS_DIR_NAME, DOWN_MIGRATIONS_DIR_NAME, DOWN_SURQL_FILE_EXTENSION, DOWN_TAG,
+        EVENTS_DIR_NAME, JSON_FILE_EXTENSION, MIGRATIONS_DIR_NAME, ROOT_TAG, SCHEMAS_DIR_NAME,
+        SURQL_FILE_EXTENSION,
     },
+    error::MigrationError,
     file::SurqlFile,
     models::{DefinitionDiff, MigrationDirection, SchemaMigrationDefinition, ScriptMigration},
+    source::{FileSystemSource, MigrationSource},
     surrealdb::parse_statements,
     tags::{extract_file_tags, is_valid_tag},
 };
 
-pub fn concat_path(folder_path: &Option<String>, dir_name: &str) -> PathBuf {
-    match folder_path.to_owned() {
-        Some(folder_path) => Path::new(&folder_path).join(dir_name),
-        None => Path::new(dir_name).to_path_buf(),
-    }
-}
-
-pub fn can_use_filesystem(config_file: Option<&Path>) -> Result<bool> {
-    let folder_path = config::retrieve_folder_path(config_file);
-    let script_migration_path = concat_path(&folder_path, SCHEMAS_DIR_NAME).join(format!(
-        "{SCRIPT_MIGRATION_TABLE_NAME}{SURQL_FILE_EXTENSION}"
-    ));
+pub fn can_use_filesystem(source: &FileSystemSource, history_table: &str) -> Result<bool> {
+    let script_migration_path = source
+        .root()
+        .join(SCHEMAS_DIR_NAME)
+        .join(format!("{history_table}{SURQL_FILE_EXTENSION}"));
     let script_migration_file_try_exists = script_migration_path.try_exists().ok();
 
     let can_use_filesystem = script_migration_file_try_exists.unwrap_or(false);
@@ -144,57 +136,49 @@ fn extract_consumed_table_names(query: &Query) -> HashSet<String> {
 }
 
 pub fn extract_schemas_files(
-    config_file: Option<&Path>,
-    embedded_dir: Option<&Dir<'static>>,
+    source: &dyn MigrationSource,
     tags: &HashSet<String>,
     exclude_tags: &HashSet<String>,
 ) -> Result<Vec<SurqlFile>> {
-    let dir_path = Path::new(SCHEMAS_DIR_NAME).to_path_buf();
+    let files = source.list_schemas()?;
     extract_surql_files(
-        config_file,
-        dir_path,
-        embedded_dir,
-        false,
+        source,
+        Path::new(SCHEMAS_DIR_NAME),
+        files,
         tags,
         exclude_tags,
     )
 }
 
 pub fn extract_events_files(
-    config_file: Option<&Path>,
-    embedded_dir: Option<&Dir<'static>>,
+    source: &dyn MigrationSource,
     tags: &HashSet<String>,
     exclude_tags: &HashSet<String>,
 ) -> Result<Vec<SurqlFile>> {
-    let dir_path = Path::new(EVENTS_DIR_NAME).to_path_buf();
+    let files = source.list_events()?;
     extract_surql_files(
-        config_file,
-        dir_path,
-        embedded_dir,
-        false,
+        source,
+        Path::new(EVENTS_DIR_NAME),
+        files,
         tags,
         exclude_tags,
     )
 }
 
 pub fn extract_migrations_files(
-    config_file: Option<&Path>,
-    embedded_dir: Option<&Dir<'static>>,
+    source: &dyn MigrationSource,
     migration_direction: MigrationDirection,
     tags: &HashSet<String>,
     exclude_tags: &HashSet<String>,
 ) -> Vec<SurqlFile> {
-    let root_migrations_dir = Path::new(MIGRATIONS_DIR_NAME).to_path_buf();
-    let root_migrations_files = extract_surql_files(
-        config_file,
-        root_migrations_dir,
-        embedded_dir,
-        true,
-        tags,
-        exclude_tags,
-    )
-    .ok()
-    .unwrap_or_default();
+    let root_migrations_dir = Path::new(MIGRATIONS_DIR_NAME);
+    let root_migrations_files = source
+        .list_migrations()
+        .and_then(|files| {
+            extract_surql_files(source, root_migrations_dir, files, tags, exclude_tags)
+        })
+        .ok()
+        .unwrap_or_default();
 
     let root_migrations_files = root_migrations_files
         .into_iter()
@@ -210,90 +194,85 @@ pub fn extract_migrations_files(
 }
 
 fn extract_surql_files(
-    config_file: Option<&Path>,
-    dir_path: PathBuf,
-    embedded_dir: Option<&Dir<'static>>,
-    is_migration_folder: bool,
-    tags: &HashSet<String>,
+    source: &dyn MigrationSource,
+    dir_path: &Path,
+    files: Vec<PathBuf>,
+    filter_tags: &HashSet<String>,
     exclude_tags: &HashSet<String>,
 ) -> Result<Vec<SurqlFile>> {
-    match embedded_dir {
-        Some(dir) => extract_surql_files_from_embedded_dir(
-            dir_path,
-            dir,
-            is_migration_folder,
-            None,
+    let mut surql_files = Vec::new();
+
+    for path in files {
+        let (Some(name), Some(full_name)) = (get_file_name(&path), get_file_full_name(&path))
+        else {
+            continue;
+        };
+
+        if !full_name.ends_with(SURQL_FILE_EXTENSION) {
+            continue;
+        }
+
+        let folder_tags = extract_folder_tags(dir_path, &path);
+        let file_tags = extract_file_tags(&full_name);
+
+        let mut tags = file_tags
+            .union(&folder_tags)
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        let is_down_file = full_name.ends_with(DOWN_SURQL_FILE_EXTENSION)
+            || path
+                .parent()
+                .is_some_and(|parent| parent.ends_with(DOWN_MIGRATIONS_DIR_NAME));
+
+        if is_down_file {
+            tags.insert(DOWN_TAG.into());
+        }
+
+        let content = source.read_file(&path)?;
+
+        let file = SurqlFile {
+            name,
+            full_name,
             tags,
-            exclude_tags,
-        ),
-        None => extract_surql_files_from_filesystem(config_file, dir_path, tags, exclude_tags),
+            content: Box::new(move || content.clone()),
+        };
+
+        if file.filter_by_tags(filter_tags, exclude_tags) {
+            surql_files.push(file);
+        }
     }
+
+    Ok(surql_files)
 }
 
-fn extract_surql_files_from_embedded_dir(
-    dir_path: PathBuf,
-    dir: &Dir<'static>,
-    is_migration_folder: bool,
-    parent_tags: Option<HashSet<String>>,
-    filter_tags: &HashSet<String>,
-    exclude_tags: &HashSet<String>,
-) -> Result<Vec<SurqlFile>> {
-    let dir_path_str = dir_path.display().to_string();
-
-    let dir = dir
-        .get_dir(&dir_path_str)
-        .context(format!("{} directory not found", &dir_path_str))?;
-
-    let files = dir
-        .files()
-        .filter_map(|f| {
-            let name = get_embedded_file_name(f);
-            let full_name = get_embedded_file_full_name(f);
-            let is_file = get_embedded_file_is_file(&full_name);
-
-            match (is_file, name, full_name) {
-                (false, ..) => None,
-                (_, Some(name), Some(full_name)) => {
-                    let path_str = f.path().to_str().unwrap_or_default();
-                    // TODO : extract tags from path folders
-
-                    let parent_tags = match &parent_tags {
-                        Some(tags) => tags,
-                        None => &HashSet::from([ROOT_TAG.into()]),
-                    };
-                    let file_tags = extract_file_tags(&full_name);
-
-                    let mut tags = file_tags
-                        .union(parent_tags)
-                        .cloned()
-                        .collect::<HashSet<_>>();
-
-                    let is_down_file = full_name.ends_with(DOWN_SURQL_FILE_EXTENSION)
-                        || (is_migration_folder
-                            && path_str.contains(&format!("{DOWN_MIGRATIONS_DIR_NAME}/")));
-
-                    if is_down_file {
-                        tags.insert(DOWN_TAG.into());
-                    }
-
-                    Some(SurqlFile {
-                        name,
-                        full_name,
-                        tags,
-                        content: Box::new(move || get_embedded_file_content(f)),
-                    })
-                }
-                _ => None,
-            }
+/// Files at the root of the folder are tagged `root`, files in nested folders are tagged with the name of these folders.
+fn extract_folder_tags(dir_path: &Path, path: &Path) -> HashSet<String> {
+    let relative_path = path.strip_prefix(dir_path).unwrap_or(path);
+
+    let folder_names = relative_path
+        .parent()
+        .map(|parent| {
+            parent
+                .iter()
+                .filter_map(|folder_name| folder_name.to_str())
+                .collect_vec()
         })
-        .filter(|file| file.filter_by_tags(filter_tags, exclude_tags))
-        .collect::<Vec<_>>();
+        .unwrap_or_default();
 
-    Ok(files)
+    if folder_names.is_empty() {
+        return HashSet::from([ROOT_TAG.into()]);
+    }
+
+    folder_names
+        .into_iter()
+        .filter(|folder_name| is_valid_tag(folder_name))
+        .map(|folder_name| folder_name.to_string())
+        .collect()
 }
 
-fn get_embedded_file_name(f: &include_dir::File) -> Option<String> {
-    let name = f.path().file_stem();
+fn get_file_name(path: &Path) -> Option<String> {
+    let name = path.file_stem();
     let name = match name {
         Some(name) if name.to_str().map(|n| n.ends_with(".down")) == Some(true) => {
             Path::new(name).file_stem()
@@ -306,150 +285,13 @@ fn get_embedded_file_name(f: &include_dir::File) -> Option<String> {
         .map(|name| name.to_string())
 }
 
-fn get_embedded_file_full_name(f: &include_dir::File) -> Option<String> {
-    f.path()
-        .file_name()
+fn get_file_full_name(path: &Path) -> Option<String> {
+    path.file_name()
         .and_then(|full_name| full_name.to_str())
         .map(|full_name| full_name.to_string())
 }
 
-fn get_embedded_file_is_file(full_name: &Option<String>) -> bool {
-    match full_name {
-        Some(full_name) => {
-            full_name.ends_with(SURQL_FILE_EXTENSION) || full_name.ends_with(JSON_FILE_EXTENSION)
-        }
-        None => false,
-    }
-}
-
-fn get_embedded_file_content(f: &include_dir::File) -> Option<String> {
-    f.contents_utf8().map(|content| content.to_string())
-}
-
-fn extract_surql_files_from_filesystem(
-    config_file: Option<&Path>,
-    dir_path: PathBuf,
-    filter_tags: &HashSet<String>,
-    exclude_tags: &HashSet<String>,
-) -> Result<Vec<SurqlFile>> {
-    let dir_path_str = dir_path.display().to_string();
-
-    let folder_path = config::retrieve_folder_path(config_file);
-    let dir_path = concat_path(&folder_path, &dir_path_str);
-
-    let mut config = HashSet::new();
-    config.insert(DirEntryAttr::Name);
-    config.insert(DirEntryAttr::Path);
-    config.insert(DirEntryAttr::IsFile);
-    config.insert(DirEntryAttr::IsDir);
-    config.insert(DirEntryAttr::FullName);
-
-    nested_extract_surql_files_from_filesystem(dir_path, &config, None, filter_tags, exclude_tags)
-        .context(format!("Error listing {dir_path_str} directory"))
-}
-
-fn nested_extract_surql_files_from_filesystem(
-    dir_path: PathBuf,
-    config: &HashSet<DirEntryAttr>,
-    parent_tags: Option<&HashSet<String>>,
-    filter_tags: &HashSet<String>,
-    exclude_tags: &HashSet<String>,
-) -> Option<Vec<SurqlFile>> {
-    let Ok(file_result) = fs_extra::dir::ls(dir_path, config) else {
-        return None;
-    };
-
-    Some(
-        file_result
-            .items
-            .iter()
-            .flat_map(|f| {
-                let is_dir =
-                    extract_boolean_dir_entry_value(f, DirEntryAttr::IsDir).unwrap_or(&false);
-                let Some(path) = extract_string_dir_entry_value(f, DirEntryAttr::Path) else {
-                    return vec![];
-                };
-
-                let Some(full_name) = extract_string_dir_entry_value(f, DirEntryAttr::FullName)
-                else {
-                    return vec![];
-                };
-
-                if *is_dir {
-                    let dir_tag = full_name.to_string();
-                    let is_valid_tag = is_valid_tag(&dir_tag);
-                    let parent_tags = match &parent_tags {
-                        Some(tags) if is_valid_tag => &HashSet::from_iter(
-                            tags.union(&HashSet::from([dir_tag])).cloned().collect_vec(),
-                        ),
-                        Some(tags) => tags,
-                        None if is_valid_tag => &HashSet::from([dir_tag]),
-                        _ => &HashSet::new(),
-                    };
-
-                    return nested_extract_surql_files_from_filesystem(
-                        path.into(),
-                        config,
-                        Some(parent_tags),
-                        filter_tags,
-                        exclude_tags,
-                    )
-                    .unwrap_or_default();
-                }
-
-                let is_file =
-                    extract_boolean_dir_entry_value(f, DirEntryAttr::IsFile).unwrap_or(&false);
-                let name = extract_string_dir_entry_value(f, DirEntryAttr::Name);
-
-                match (is_file, name) {
-                    (false, ..) => vec![],
-                    (true, Some(name)) if full_name.ends_with(SURQL_FILE_EXTENSION) => {
-                        let parent_tags = match &parent_tags {
-                            Some(tags) => tags,
-                            None => &HashSet::from([ROOT_TAG.into()]),
-                        };
-                        let file_tags = extract_file_tags(full_name);
-
-                        let tags = file_tags.union(parent_tags).cloned().collect();
-
-                        let path: String = path.clone();
-
-                        vec![SurqlFile {
-                            name: name.to_string(),
-                            full_name: full_name.to_string(),
-                            tags,
-                            content: Box::new(move || fs_extra::file::read_to_string(&path).ok()),
-                        }]
-                    }
-                    _ => vec![],
-                }
-            })
-            .filter(|file| file.filter_by_tags(filter_tags, exclude_tags))
-            .collect::<Vec<_>>(),
-    )
-}
-
-fn extract_boolean_dir_entry_value(
-    f: &HashMap<DirEntryAttr, DirEntryValue>,
-    entry_attribute: DirEntryAttr,
-) -> Option<&bool> {
-    if let Some(DirEntryValue::Boolean(value)) = f.get(&entry_attribute) {
-        return Some(value);
-    }
-    None
-}
-
-fn extract_string_dir_entry_value(
-    f: &HashMap<DirEntryAttr, DirEntryValue>,
-    entry_attribute: DirEntryAttr,
-) -> Option<&String> {
-    if let Some(DirEntryValue::String(value)) = f.get(&entry_attribute) {
-        return Some(value);
-    }
-    None
-}
-
-fn get_sorted_migrations_files(migrations_files: Vec<SurqlFile>) -> Vec<SurqlFile> {
+pub fn get_sorted_migrations_files(migrations_files: Vec<SurqlFile>) -> Vec<SurqlFile> {
     let mut sorted_migrations_files = migrations_files;
     sorted_migrations_files.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name));
 
@@ -476,144 +318,114 @@ impl JsonDefinitionFile {
 }
 
 pub fn extract_json_definition_files(
-    config_file: Option<&Path>,
-    dir_path: &Path,
-    embedded_dir: Option<&Dir<'static>>,
-) -> Result<Vec<JsonDefinitionFile>> {
-    match embedded_dir {
-        Some(dir) => extract_json_definition_files_from_embedded_dir(dir_path, dir),
-        None => extract_json_definition_files_from_filesystem(config_file, dir_path),
-    }
-}
-
-fn extract_json_definition_files_from_embedded_dir(
-    dir_path: &Path,
-    dir: &Dir<'static>,
-) -> Result<Vec<JsonDefinitionFile>> {
-    let dir_path_str = dir_path.display().to_string();
-
-    let dir = dir
-        .get_dir(&dir_path_str)
-        .context(format!("{} directory not found", &dir_path_str))?;
-
-    let files = dir
-        .files()
-        .filter_map(|f| {
-            let name = get_embedded_file_name(f);
-            let full_name = get_embedded_file_full_name(f);
-            let is_file = get_embedded_file_is_file(&full_name);
-
-            match (is_file, name) {
-                (false, ..) => None,
-                (_, Some(name)) => Some(JsonDefinitionFile {
-                    name,
-                    content: Box::new(move || get_embedded_file_content(f)),
-                }),
-                _ => None,
-            }
-        })
-        .collect::<Vec<_>>();
-
-    Ok(files)
-}
-
-fn extract_json_definition_files_from_filesystem(
-    config_file: Option<&Path>,
-    dir_path: &Path,
+    source: &dyn MigrationSource,
 ) -> Result<Vec<JsonDefinitionFile>> {
-    let dir_path_str = dir_path.display().to_string();
+    let definitions_path = Path::new(DEFINITIONS_DIR_NAME);
 
-    let folder_path = config::retrieve_folder_path(config_file);
-    let dir_path = concat_path(&folder_path, &dir_path_str);
+    let mut files = Vec::new();
 
-    if !dir_path.exists() {
-        return Ok(vec![]);
-    }
+    for path in source.list_definitions()? {
+        let is_definition_file = path.parent() == Some(definitions_path)
+            && get_file_full_name(&path)
+                .is_some_and(|full_name| full_name.ends_with(JSON_FILE_EXTENSION));
 
-    let mut config = HashSet::new();
-    config.insert(DirEntryAttr::Name);
-    config.insert(DirEntryAttr::Path);
-    config.insert(DirEntryAttr::IsFile);
+        let name = path.file_stem().and_then(|name| name.to_str());
 
-    let files = fs_extra::dir::ls(dir_path, &config)
-        .context(format!("Error listing {dir_path_str} directory"))?
-        .items;
+        match (is_definition_file, name) {
+            (true, Some(name)) => {
+                let name = name.to_string();
+                let content = source.read_file(&path)?;
 
-    let files = files
-        .iter()
-        .filter_map(|f| {
-            let is_file = extract_boolean_dir_entry_value(f, DirEntryAttr::IsFile);
-            let name = extract_string_dir_entry_value(f, DirEntryAttr::Name);
-            let path = extract_string_dir_entry_value(f, DirEntryAttr::Path);
-
-            match (is_file, name, path) {
-                (None, ..) => None,
-                (Some(false), ..) => None,
-                (_, Some(name), Some(path)) => {
-                    let path = path.clone();
-
-                    Some(JsonDefinitionFile {
-                        name: name.to_string(),
-                        content: Box::new(move || fs_extra::file::read_to_string(&path).ok()),
-                    })
-                }
-                _ => None,
+                files.push(JsonDefinitionFile {
+                    name,
+                    content: Box::new(move || content.clone()),
+                });
             }
-        })
-        .collect::<Vec<_>>();
+            _ => continue,
+        }
+    }
 
     Ok(files)
 }
 
-pub fn create_definition_files(
-    config_file: Option<&Path>,
+/// A change to apply to a definition file, the path being relative to the root of the source.
+pub struct DefinitionFileChange {
+    pub path: PathBuf,
+    /// The new content of the file, or `None` if the file should be removed.
+    pub content: Option<String>,
+}
+
+/// Compute the changes of the definition files without writing them.
+pub fn get_definition_files_changes(
+    source: &FileSystemSource,
     definitions_path: PathBuf,
     initial_definition_path: PathBuf,
     schema_definitions: String,
     event_definitions: String,
     tags: &HashSet<String>,
     exclude_tags: &HashSet<String>,
-) -> Result<()> {
-    let forward_migrations_files = extract_migrations_files(
-        config_file,
-        None,
-        MigrationDirection::Forward,
-        tags,
-        exclude_tags,
-    );
+) -> Result<Vec<DefinitionFileChange>> {
+    let forward_migrations_files =
+        extract_migrations_files(source, MigrationDirection::Forward, tags, exclude_tags);
     let last_migration_file = forward_migrations_files.last();
 
-    if let Some(last_migration_file) = last_migration_file {
-        update_migration_definition_file(
-            config_file,
+    match last_migration_file {
+        Some(last_migration_file) => get_migration_definition_file_changes(
+            source,
             definitions_path,
             initial_definition_path,
             last_migration_file,
             schema_definitions,
             event_definitions,
-        )?;
-    } else {
-        create_initial_definition_file(
-            config_file,
-            &definitions_path,
-            &initial_definition_path,
-            schema_definitions,
-            event_definitions,
-        )?;
+        ),
+        None => {
+            let initial_definition_change = get_initial_definition_file_change(
+                initial_definition_path,
+                schema_definitions,
+                event_definitions,
+            )?;
+
+            Ok(vec![initial_definition_change])
+        }
+    }
+}
+
+pub fn write_definition_files(
+    source: &FileSystemSource,
+    changes: Vec<DefinitionFileChange>,
+) -> Result<()> {
+    for change in changes {
+        let file_path = source.root().join(&change.path);
+
+        match change.content {
+            Some(content) => {
+                if let Some(dir_path) = file_path.parent() {
+                    ensures_folder_exists(&dir_path.to_path_buf())?;
+                }
+                fs_extra::file::write_all(&file_path, &content)?;
+            }
+            None => {
+                if file_path.exists() {
+                    fs_extra::file::remove(&file_path)?;
+                }
+            }
+        }
     }
 
     Ok(())
 }
 
-fn update_migration_definition_file(
-    config_file: Option<&Path>,
+fn get_migration_definition_file_changes(
+    source: &FileSystemSource,
     definitions_path: PathBuf,
     initial_definition_path: PathBuf,
     last_migration_file: &SurqlFile,
     schema_definitions: String,
     event_definitions: String,
-) -> Result<()> {
-    let mut definition_files = extract_json_definition_files(config_file, &definitions_path, None)?;
+) -> Result<Vec<DefinitionFileChange>> {
+    let mut changes = Vec::new();
+
+    let mut definition_files = extract_json_definition_files(source)?;
     definition_files.sort_by(|a, b| a.name.cmp(&b.name));
     let definition_files = definition_files;
 
@@ -621,19 +433,21 @@ fn update_migration_definition_file(
 
     let initial_definition_str = match initial_definition_file {
         Some(initial_definition_file) => initial_definition_file.get_content().unwrap_or_default(),
-        None => create_initial_definition_file(
-            config_file,
-            &definitions_path,
-            &initial_definition_path,
-            schema_definitions.to_string(),
-            event_definitions.to_string(),
-        )?,
-    };
-
-    let folder_path = config::retrieve_folder_path(config_file);
-    let definitions_path = match &folder_path {
-        Some(folder_path) => Path::new(&folder_path).join(definitions_path),
-        None => definitions_path,
+        None => {
+            let initial_definition_change = get_initial_definition_file_change(
+                initial_definition_path,
+                schema_definitions.to_string(),
+                event_definitions.to_string(),
+            )?;
+            let initial_definition_str = initial_definition_change
+                .content
+                .clone()
+                .unwrap_or_default();
+
+            changes.push(initial_definition_change);
+
+            initial_definition_str
+        }
     };
 
     let initial_definition =
@@ -689,52 +503,43 @@ fn update_migration_definition_file(
     match has_changes {
         true => {
             // Create definition file if any changes
-            ensures_folder_exists(&definitions_path)?;
-
             let serialized_definition = serde_json::to_string(&definition_diff)?;
-            fs_extra::file::write_all(&definition_filepath, &serialized_definition)?;
+
+            changes.push(DefinitionFileChange {
+                path: definition_filepath,
+                content: Some(serialized_definition),
+            });
         }
         false => {
             // Remove definition file if exists
-            let definition_filepath = Path::new(&definition_filepath);
-
-            if definition_filepath.exists() {
-                fs_extra::file::remove(definition_filepath)?;
+            if source.read_file(&definition_filepath)?.is_some() {
+                changes.push(DefinitionFileChange {
+                    path: definition_filepath,
+                    content: None,
+                });
             }
         }
     };
 
-    Ok(())
+    Ok(changes)
 }
 
-fn create_initial_definition_file(
-    config_file: Option<&Path>,
-    definitions_path: &PathBuf,
-    initial_definition_path: &PathBuf,
+fn get_initial_definition_file_change(
+    initial_definition_path: PathBuf,
     schema_definitions: String,
     event_definitions: String,
-) -> Result<String> {
-    let folder_path = config::retrieve_folder_path(config_file);
-    let definitions_path = match &folder_path {
-        Some(folder_path) => Path::new(&folder_path).join(definitions_path),
-        None => definitions_path.clone(),
-    };
-    let initial_definition_path = match &folder_path {
-        Some(folder_path) => Path::new(&folder_path).join(initial_definition_path),
-        None => initial_definition_path.clone(),
-    };
-
-    ensures_folder_exists(&definitions_path)?;
-
+) -> Result<DefinitionFileChange> {
     let current_definition = SchemaMigrationDefinition {
         schemas: schema_definitions,
         events: event_definitions,
     };
 
     let serialized_definition = serde_json::to_string(&current_definition)?;
-    fs_extra::file::write_all(initial_definition_path, &serialized_definition)?;
 
-    Ok(serialized_definition)
+    Ok(DefinitionFileChange {
+        path: initial_definition_path,
+        content: Some(serialized_definition),
+    })
 }
 
 pub fn filter_except_initial_definition(file: &JsonDefinitionFile) -> bool {
@@ -767,20 +572,23 @@ pub fn calculate_definition_using_patches(
 }
 
 pub fn apply_patch(text: String, diff: String) -> Result<String> {
-    let patch = diffy::Patch::from_str(&diff)?;
-    let value = diffy::apply(&text, &patch)?;
+    let patch = diffy::Patch::from_str(&diff).map_err(to_definition_patch_error)?;
+    let value = diffy::apply(&text, &patch).map_err(to_definition_patch_error)?;
 
     Ok(value)
 }
 
+fn to_definition_patch_error(error: impl ToString) -> MigrationError {
+    MigrationError::DefinitionPatchFailed {
+        message: error.to_string(),
+    }
+}
+
 pub fn get_current_definition(
-    config_file: Option<&Path>,
-    definitions_path: PathBuf,
+    source: &dyn MigrationSource,
     last_migration_applied: &ScriptMigration,
-    embedded_dir: Option<&Dir<'static>>,
 ) -> Result<SchemaMigrationDefinition> {
-    let mut definition_files =
-        extract_json_definition_files(config_file, &definitions_path, embedded_dir)?;
+    let mut definition_files = extract_json_definition_files(source)?;
     definition_files.sort_by(|a, b| a.name.cmp(&b.name));
     let definition_files = definition_files;
 
@@ -814,72 +622,35 @@ fn take_while_applied_or_before(
     file.name <= last_migration_applied.script_name
 }
 
-pub fn get_initial_definition(
-    config_file: Option<&Path>,
-    definitions_path: PathBuf,
-    embedded_dir: Option<&Dir<'static>>,
-) -> Result<SchemaMigrationDefinition> {
-    let definition_str =
-        extract_initial_definition_content(config_file, definitions_path, embedded_dir)?;
+pub fn get_initial_definition(source: &dyn MigrationSource) -> Result<SchemaMigrationDefinition> {
+    let definition_str = extract_initial_definition_content(source)?;
     let definition = serde_json::from_str::<SchemaMigrationDefinition>(&definition_str)?;
 
     Ok(definition)
 }
 
-fn extract_initial_definition_content(
-    config_file: Option<&Path>,
-    definitions_path: PathBuf,
-    embedded_dir: Option<&Dir<'static>>,
-) -> Result<String> {
+fn extract_initial_definition_content(source: &dyn MigrationSource) -> Result<String> {
     const INITIAL_DEFINITION_FILENAME: &str = "_initial.json";
 
-    match embedded_dir {
-        Some(dir) => {
-            let dir_path_str = definitions_path.display().to_string();
-            let path = definitions_path.join(INITIAL_DEFINITION_FILENAME);
+    let initial_definition_filepath =
+        Path::new(DEFINITIONS_DIR_NAME).join(INITIAL_DEFINITION_FILENAME);
 
-            let file = dir.get_file(path).context(format!(
-                "{} file not found in {} directory",
-                INITIAL_DEFINITION_FILENAME, &dir_path_str
-            ))?;
+    let content = source
+        .read_file(&initial_definition_filepath)?
+        .context(format!(
+            "{INITIAL_DEFINITION_FILENAME} file not found in {DEFINITIONS_DIR_NAME} directory"
+        ))?;
 
-            let content = get_embedded_file_content(file).context(format!(
-                "Error while reading {} file in {} directory",
-                INITIAL_DEFINITION_FILENAME, &dir_path_str
-            ))?;
-
-            Ok(content)
-        }
-        None => {
-            let folder_path = config::retrieve_folder_path(config_file);
-            let definitions_path = match &folder_path {
-                Some(folder_path) => Path::new(&folder_path).join(definitions_path),
-                None => definitions_path,
-            };
-
-            let initial_definition_filepath = definitions_path.join(INITIAL_DEFINITION_FILENAME);
-            let content =
-                fs_extra::file::read_to_string(&initial_definition_filepath).wrap_err(format!(
-                    "initial_definition_filepath not found at: {initial_definition_filepath:?}",
-                ))?;
-
-            Ok(content)
-        }
-    }
+    Ok(content)
 }
 
 pub fn get_migration_definition_diff(
-    config_file: Option<&Path>,
-    definitions_path: PathBuf,
+    source: &dyn MigrationSource,
     migration_name: String,
-    embedded_dir: Option<&Dir<'static>>,
 ) -> Result<Option<DefinitionDiff>> {
-    let definition_str = extract_definition_diff_content(
-        config_file,
-        definitions_path,
-        migration_name,
-        embedded_dir,
-    )?;
+    let definition_filepath =
+        Path::new(DEFINITIONS_DIR_NAME).join(format!("{migration_name}.json"));
+    let definition_str = source.read_file(&definition_filepath)?;
 
     if let Some(definition_str) = definition_str {
         let definition = serde_json::from_str::<DefinitionDiff>(&definition_str)?;
@@ -889,57 +660,10 @@ pub fn get_migration_definition_diff(
     }
 }
 
-fn extract_definition_diff_content(
-    config_file: Option<&Path>,
-    definitions_path: PathBuf,
-    migration_name: String,
-    embedded_dir: Option<&Dir<'static>>,
-) -> Result<Option<String>> {
-    let definition_filename = format!("{migration_name}.json");
-
-    match embedded_dir {
-        Some(dir) => {
-            let dir_path_str = definitions_path.display().to_string();
-            let path = definitions_path.join(&definition_filename);
-
-            if dir.contains(&path) {
-                let file = dir.get_file(&path).context(format!(
-                    "{} file not found in {} directory",
-                    &definition_filename, &dir_path_str
-                ))?;
-
-                let content = get_embedded_file_content(file).context(format!(
-                    "Error while reading {} file in {} directory",
-                    definition_filename, &dir_path_str
-                ))?;
-
-                Ok(Some(content))
-            } else {
-                Ok(None)
-            }
-        }
-        None => {
-            let folder_path = config::retrieve_folder_path(config_file);
-            let definitions_path = match &folder_path {
-                Some(folder_path) => Path::new(&folder_path).join(definitions_path),
-                None => definitions_path,
-            };
-
-            let definition_filepath = definitions_path.join(definition_filename);
-
-            if definition_filepath.exists() {
-                let content = fs_extra::file::read_to_string(&definition_filepath)?;
-                Ok(Some(content))
-            } else {
-                Ok(None)
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{constants::ALL_TAGS, memory_source::InMemorySource};
 
     fn create_surql_file(full_name: &str, content: &'static str) -> SurqlFile {
         SurqlFile {
@@ -967,4 +691,41 @@ mod tests {
         let result = concat_files_content(&files);
         assert_eq!(result, "Text of a file\nText of b file\nText of c file");
     }
+
+    #[test]
+    fn root_files_have_root_tag() {
+        let result = extract_folder_tags(
+            Path::new("migrations"),
+            Path::new("migrations/20231225_AddPost.surql"),
+        );
+        assert_eq!(result, HashSet::from([ROOT_TAG.into()]));
+    }
+
+    #[test]
+    fn nested_files_are_tagged_with_folder_names() {
+        let result = extract_folder_tags(
+            Path::new("migrations"),
+            Path::new("migrations/v2/down/20231225_AddPost.surql"),
+        );
+        assert_eq!(result, HashSet::from(["v2".into(), DOWN_TAG.into()]));
+    }
+
+    #[test]
+    fn extract_down_migrations_files_from_source() {
+        let source = InMemorySource::new()
+            .with_file("migrations/20231225_AddPost.surql", "")
+            .with_file("migrations/20231225_AddPost.down.surql", "")
+            .with_file("migrations/20231226_AddUser.surql", "")
+            .with_file("migrations/down/20231226_AddUser.surql", "")
+            .with_file("migrations/definitions/_initial.json", "");
+
+        let tags = HashSet::from([ALL_TAGS.into()]);
+        let exclude_tags = HashSet::new();
+
+        let files =
+            extract_migrations_files(&source, MigrationDirection::Backward, &tags, &exclude_tags);
+
+        let names = files.iter().map(|file| file.name.as_str()).collect_vec();
+        assert_eq!(names, vec!["20231225_AddPost", "20231226_AddUser"]);
+    }
 }
@@ -54,13 +54,31 @@
 //! ```
 
 mod apply;
+mod baseline;
+mod code_migration;
 mod common;
 mod config;
 mod constants;
+// the embedded and in-memory sources are only exposed by the library, the CLI reads from the filesystem
+#[path = "source/embedded.rs"]
+mod embedded_source;
+mod error;
+mod fake;
 mod file;
 mod io;
+mod lock;
+#[path = "source/memory.rs"]
+mod memory_source;
+mod migration_event;
+mod migration_repair;
+mod migration_status;
 mod models;
+mod observer;
 mod redo;
+mod rollback;
+mod schema_snapshot;
+mod script_migration;
+mod source;
 mod surrealdb;
 mod tags;
 mod validate_checksum;
@@ -68,21 +86,44 @@ mod validate_version_order;
 
 use ::surrealdb::{Connection, Surreal};
 use apply::ApplyArgs;
-use color_eyre::eyre::Result;
+use baseline::BaselineArgs;
+use code_migration::NamedCodeMigration;
+use fake::{FakeArgs, FakeOperation};
 use include_dir::Dir;
-use models::{ApplyOperation, ScriptMigration};
+use lock::LockOptions;
+use migration_repair::MigrationRepairArgs;
+use migration_status::MigrationStatusArgs;
 use redo::RedoArgs;
-use std::{collections::HashSet, path::Path};
+use std::{collections::HashSet, path::Path, time::Duration};
 use validate_checksum::ValidateChecksumArgs;
 use validate_version_order::ValidateVersionOrderArgs;
 
+pub use code_migration::{CodeMigration, MigrationFuture};
+pub use embedded_source::EmbeddedSource;
+pub use error::MigrationError;
+pub use memory_source::InMemorySource;
+pub use models::{
+    ApplyOperation, ApplyReport, ChecksumAlgorithm, MigrationDirection, MigrationEvent,
+    MigrationPlan, MigrationPlanStep, MigrationPlanStepKind, MigrationRepair, MigrationReport,
+    MigrationStatus, ScriptMigration,
+};
+pub use observer::MigrationObserver;
+pub use source::{FileSystemSource, MigrationSource};
+
 /// The main entry point for the library, used to apply migrations.
 pub struct MigrationRunner<'a, C: Connection> {
     db: &'a Surreal<C>,
-    dir: Option<&'a Dir<'static>>,
+    source: Option<Box<dyn MigrationSource + 'a>>,
     config_file: Option<&'a Path>,
+    history_table: Option<String>,
     tags: Option<HashSet<String>>,
     exclude_tags: Option<HashSet<String>>,
+    observer: Option<&'a dyn MigrationObserver>,
+    code_migrations: Vec<NamedCodeMigration<'a, C>>,
+    lock: Option<LockOptions>,
+    checksum_algorithm: Option<ChecksumAlgorithm>,
+    out_of_order: Option<bool>,
+    executor: Option<String>,
 }
 
 impl<'a, C: Connection> MigrationRunner<'a, C> {
@@ -94,10 +135,17 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     pub fn new(db: &'a Surreal<C>) -> Self {
         MigrationRunner {
             db,
-            dir: None,
+            source: None,
             config_file: None,
+            history_table: None,
             tags: None,
             exclude_tags: None,
+            observer: None,
+            code_migrations: Vec::new(),
+            lock: Some(LockOptions::default()),
+            checksum_algorithm: None,
+            out_of_order: None,
+            executor: None,
         }
     }
 
@@ -141,24 +189,34 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     pub fn use_config_file<P: AsRef<Path>>(self, config_file: &'a P) -> Self {
         MigrationRunner {
             db: self.db,
-            dir: self.dir,
+            source: self.source,
             config_file: Some(config_file.as_ref()),
+            history_table: self.history_table,
             tags: self.tags,
             exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
         }
     }
 
-    /// Load migration project files from an embedded directory.
+    /// Set the name of the table used to store the history of applied migrations.
+    /// By default, it will try to read it from the configuration file (`[core] history_table`),
+    /// or use the `script_migration` table.
+    ///
+    /// The table should be defined in a schema file of the same name, e.g. `schemas/module_migration.surql`.
     ///
     /// ## Arguments
     ///
-    /// * `dir` - The directory containing the migration project files.
+    /// * `history_table` - Name of the history table.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
-    /// use include_dir::{include_dir, Dir};
     /// use surrealdb_migrations::MigrationRunner;
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
@@ -176,32 +234,36 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// // Load migration project files from an embedded directory
-    /// const DB_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/blog");
-    ///
     /// let runner = MigrationRunner::new(&db)
-    ///     .load_files(&DB_DIR) // Will look for embedded files instead of the filesystem
+    ///     .use_history_table("billing_migration")
     ///     .up()
     ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn load_files(self, dir: &'a Dir<'static>) -> Self {
+    pub fn use_history_table(self, history_table: &str) -> Self {
         MigrationRunner {
             db: self.db,
-            dir: Some(dir),
+            source: self.source,
             config_file: self.config_file,
+            history_table: Some(history_table.to_string()),
             tags: self.tags,
             exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
         }
     }
 
-    /// Set the list of tags to filter migrations.
+    /// Load migration project files from an embedded directory.
     ///
     /// ## Arguments
     ///
-    /// * `tags` - The list of tags to filter migrations.
+    /// * `dir` - The directory containing the migration project files.
     ///
     /// ## Examples
     ///
@@ -229,35 +291,41 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// const DB_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/blog");
     ///
     /// let runner = MigrationRunner::new(&db)
-    ///     .with_tags(HashSet::from(["v2"])) // Will run "root" migrations and migrations with the "v2" tag
+    ///     .load_files(&DB_DIR) // Will look for embedded files instead of the filesystem
     ///     .up()
     ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn with_tags(self, tags: &HashSet<&str>) -> Self {
+    pub fn load_files(self, dir: &'a Dir<'static>) -> Self {
         MigrationRunner {
             db: self.db,
-            dir: self.dir,
+            source: Some(Box::new(EmbeddedSource::new(dir))),
             config_file: self.config_file,
-            tags: Some(HashSet::from_iter(tags.iter().map(|s| s.to_string()))),
+            history_table: self.history_table,
+            tags: self.tags,
             exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
         }
     }
 
-    /// Set the list of tags to exclude when applying migrations.
+    /// Load migration project files from a custom source, see [`MigrationSource`].
     ///
     /// ## Arguments
     ///
-    /// * `exclude_tags` - A list of tags to exclude.
+    /// * `source` - The source containing the migration project files.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
-    /// use include_dir::{include_dir, Dir};
-    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb_migrations::{InMemorySource, MigrationRunner};
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
     ///
@@ -274,36 +342,51 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// // Load migration project files from an embedded directory
-    /// const DB_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/blog");
+    /// let source = InMemorySource::new()
+    ///     .with_file(
+    ///         "schemas/script_migration.surql",
+    ///         "DEFINE TABLE script_migration SCHEMAFULL;
+    /// DEFINE FIELD script_name ON script_migration TYPE string;
+    /// DEFINE FIELD executed_at ON script_migration TYPE datetime VALUE time::now() READONLY;",
+    ///     );
     ///
     /// let runner = MigrationRunner::new(&db)
-    ///     .with_exclude_tags(HashSet::from(["v2"])) // Will exclude migrations with the "v2" tag
+    ///     .with_source(source) // Will look for files in memory instead of the filesystem
     ///     .up()
     ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub fn with_exclude_tags(self, exclude_tags: &HashSet<&str>) -> Self {
+    pub fn with_source(self, source: impl MigrationSource + 'a) -> Self {
         MigrationRunner {
             db: self.db,
-            dir: self.dir,
+            source: Some(Box::new(source)),
             config_file: self.config_file,
+            history_table: self.history_table,
             tags: self.tags,
-            exclude_tags: Some(HashSet::from_iter(
-                exclude_tags.iter().map(|s| s.to_string()),
-            )),
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
         }
     }
 
-    /// Validate the version order of the migrations so that you cannot run migrations if there are
-    /// gaps in the migrations history.
+    /// Set the list of tags to filter migrations.
+    ///
+    /// ## Arguments
+    ///
+    /// * `tags` - The list of tags to filter migrations.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use std::collections::HashSet;
+    /// use include_dir::{include_dir, Dir};
     /// use surrealdb_migrations::MigrationRunner;
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
@@ -321,30 +404,46 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// let runner = MigrationRunner::new(&db);
+    /// // Load migration project files from an embedded directory
+    /// const DB_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/blog");
     ///
-    /// runner.validate_version_order().await?;
-    /// runner.up().await?;
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_tags(&HashSet::from(["v2"])) // Will run "root" migrations and migrations with the "v2" tag
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn validate_version_order(&self) -> Result<()> {
-        let args = ValidateVersionOrderArgs {
+    pub fn with_tags(self, tags: &HashSet<&str>) -> Self {
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
+            source: self.source,
             config_file: self.config_file,
-        };
-        validate_version_order::main(args).await
+            history_table: self.history_table,
+            tags: Some(HashSet::from_iter(tags.iter().map(|s| s.to_string()))),
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Validate the checksum of the migrations already played so that you cannot run migrations if there are
-    /// changes detected in the migrations directory.
+    /// Set the list of tags to exclude when applying migrations.
+    ///
+    /// ## Arguments
+    ///
+    /// * `exclude_tags` - A list of tags to exclude.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use std::collections::HashSet;
+    /// use include_dir::{include_dir, Dir};
     /// use surrealdb_migrations::MigrationRunner;
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
@@ -362,33 +461,59 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// let runner = MigrationRunner::new(&db);
+    /// // Load migration project files from an embedded directory
+    /// const DB_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/blog");
     ///
-    /// runner.validate_checksum().await?;
-    /// runner.up().await?;
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_exclude_tags(&HashSet::from(["v2"])) // Will exclude migrations with the "v2" tag
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn validate_checksum(&self) -> Result<()> {
-        let args = ValidateChecksumArgs {
+    pub fn with_exclude_tags(self, exclude_tags: &HashSet<&str>) -> Self {
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
+            source: self.source,
             config_file: self.config_file,
-        };
-        validate_checksum::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: Some(HashSet::from_iter(
+                exclude_tags.iter().map(|s| s.to_string()),
+            )),
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Apply schema definitions and apply all migrations.
+    /// Register an observer notified before and after each migration is executed.
+    /// By default, no observer is registered and the progress is only reported through `tracing` events.
+    ///
+    /// ## Arguments
+    ///
+    /// * `observer` - The observer to notify, see [`MigrationObserver`].
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
-    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb_migrations::{MigrationDirection, MigrationObserver, MigrationRunner};
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
     ///
+    /// struct PrintObserver;
+    ///
+    /// impl MigrationObserver for PrintObserver {
+    ///     fn before_migration(&self, name: &str, _direction: MigrationDirection) {
+    ///         println!("Executing migration {name}...");
+    ///     }
+    /// }
+    ///
     /// # #[tokio::main]
     /// # async fn main() -> Result<()> {
     /// let db = connect("ws://localhost:8000").await?;
@@ -402,44 +527,54 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// MigrationRunner::new(&db)
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_observer(&PrintObserver) // Will display "Executing migration ..." logs
     ///     .up()
-    ///     .await
-    ///     .expect("Failed to apply migrations");
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn up(&self) -> Result<()> {
-        let args: ApplyArgs<C> = ApplyArgs {
-            operation: ApplyOperation::Up,
+    pub fn with_observer(self, observer: &'a dyn MigrationObserver) -> Self {
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
-            display_logs: false,
-            dry_run: false,
-            validate_checksum: false,
-            validate_version_order: false,
+            source: self.source,
             config_file: self.config_file,
-            output: false,
-            tags: self.tags.clone(),
-            exclude_tags: self.exclude_tags.clone(),
-        };
-        apply::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: Some(observer),
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Apply schema definitions and all migrations up to and including the named migration.
+    /// Register a migration written in Rust, see [`CodeMigration`].
+    /// It will be ordered by name together with the `.surql` migration files.
     ///
     /// ## Arguments
     ///
-    /// * `name` - This parameter allows you to skip ulterior migrations.
+    /// * `name` - Name of the migration, e.g. `20240101_120000_NormalizeEmails`.
+    /// * `migration` - The migration to execute.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
-    /// use surrealdb_migrations::MigrationRunner;
-    /// use surrealdb::engine::any::connect;
+    /// use surrealdb_migrations::{MigrationFuture, MigrationRunner};
+    /// use surrealdb::engine::any::{connect, Any};
     /// use surrealdb::opt::auth::Root;
+    /// use surrealdb::Surreal;
+    ///
+    /// fn normalize_emails(db: &Surreal<Any>) -> MigrationFuture<'_> {
+    ///     Box::pin(async move {
+    ///         db.query("UPDATE user SET email = string::lowercase(email)").await?;
+    ///         Ok(())
+    ///     })
+    /// }
     ///
     /// # #[tokio::main]
     /// # async fn main() -> Result<()> {
@@ -454,37 +589,49 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// MigrationRunner::new(&db)
-    ///     .up_to("20230101_120002_AddPost")
-    ///     .await
-    ///     .expect("Failed to apply migrations");
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_code_migration("20240101_120000_NormalizeEmails", &normalize_emails)
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn up_to(&self, name: &str) -> Result<()> {
-        let args = ApplyArgs {
-            operation: ApplyOperation::UpTo(name.to_string()),
+    pub fn with_code_migration(self, name: &str, migration: &'a dyn CodeMigration<C>) -> Self {
+        let mut code_migrations = self.code_migrations;
+        code_migrations.push(NamedCodeMigration {
+            name: name.to_string(),
+            migration,
+        });
+
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
-            display_logs: false,
-            dry_run: false,
-            validate_checksum: false,
-            validate_version_order: false,
+            source: self.source,
             config_file: self.config_file,
-            output: false,
-            tags: self.tags.clone(),
-            exclude_tags: self.exclude_tags.clone(),
-        };
-        apply::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Apply only the next migration. This allows you to apply migrations step by step when necessary.
+    /// Set the maximum time to wait for the migration lock when another migration run holds it.
+    /// By default, it will wait up to 60 seconds before failing with [`MigrationError::LockTimeout`].
+    ///
+    /// ## Arguments
+    ///
+    /// * `timeout` - Maximum time to wait for the migration lock.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use std::time::Duration;
     /// use surrealdb_migrations::MigrationRunner;
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
@@ -502,41 +649,50 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// MigrationRunner::new(&db)
-    ///     .up_single()
-    ///     .await
-    ///     .expect("Failed to apply migration");
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_lock_timeout(Duration::from_secs(300)) // Will wait up to 5 minutes for the lock
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn up_single(&self) -> Result<()> {
-        let args = ApplyArgs {
-            operation: ApplyOperation::UpSingle,
+    pub fn with_lock_timeout(self, timeout: Duration) -> Self {
+        let lock = self.lock.map(|lock| LockOptions {
+            wait_timeout: timeout,
+            ..lock
+        });
+
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
-            display_logs: false,
-            dry_run: false,
-            validate_checksum: false,
-            validate_version_order: false,
+            source: self.source,
             config_file: self.config_file,
-            output: false,
-            tags: self.tags.clone(),
-            exclude_tags: self.exclude_tags.clone(),
-        };
-        apply::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Revert schema definitions and all migrations down to the named migration.
+    /// Set the lease of the migration lock, i.e. the time after which a lock is considered stale
+    /// (e.g. the process holding it crashed) and can be taken over by another migration run.
+    /// By default, the lease is 15 minutes. The lease is renewed while migrations are running,
+    /// a run fails with [`MigrationError::LockLost`] if the lock has been taken over in the meantime.
     ///
     /// ## Arguments
     ///
-    /// * `name` - This parameter allows you to revert applied migrations to this one.
+    /// * `lease` - Lease of the migration lock.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use std::time::Duration;
     /// use surrealdb_migrations::MigrationRunner;
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
@@ -554,32 +710,36 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// MigrationRunner::new(&db)
-    ///     .down_to("20240607_163541_AddPost")
-    ///     .await
-    ///     .expect("Failed to revert migrations");
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_lock_lease(Duration::from_secs(3600)) // Long migration runs
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn down_to(&self, name: &str) -> Result<()> {
-        let args = ApplyArgs {
-            operation: ApplyOperation::DownTo(name.to_string()),
+    pub fn with_lock_lease(self, lease: Duration) -> Self {
+        let lock = self.lock.map(|lock| LockOptions { lease, ..lock });
+
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
-            display_logs: false,
-            dry_run: false,
-            validate_checksum: false,
-            validate_version_order: false,
+            source: self.source,
             config_file: self.config_file,
-            output: false,
-            tags: self.tags.clone(),
-            exclude_tags: self.exclude_tags.clone(),
-        };
-        apply::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Revert the last applied migration. This allows you to downgrade migrations step by step when necessary.
+    /// Apply migrations without acquiring the migration lock.
+    /// By default, a lock stored in the `migration_lock` table prevents concurrent migration runs,
+    /// including baseline, fake, unfake, redo and repair operations (dry runs and plans do not take the lock).
     ///
     /// ## Examples
     ///
@@ -602,38 +762,45 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// MigrationRunner::new(&db)
-    ///     .down_single()
-    ///     .await
-    ///     .expect("Failed to revert migration");
+    /// let runner = MigrationRunner::new(&db)
+    ///     .without_lock() // Will not prevent concurrent migration runs
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn down_single(&self) -> Result<()> {
-        let args = ApplyArgs {
-            operation: ApplyOperation::DownSingle,
+    pub fn without_lock(self) -> Self {
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
-            display_logs: false,
-            dry_run: false,
-            validate_checksum: false,
-            validate_version_order: false,
+            source: self.source,
             config_file: self.config_file,
-            output: false,
-            tags: self.tags.clone(),
-            exclude_tags: self.exclude_tags.clone(),
-        };
-        apply::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: None,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Revert ALL schema definitions and migrations.
+    /// Set the algorithm used to compute the checksum of the migrations applied.
+    /// By default, the algorithm defined in the configuration file is used, or the raw algorithm if none.
+    ///
+    /// Migrations already applied are always validated with the algorithm stored alongside their checksum.
+    ///
+    /// ## Arguments
+    ///
+    /// * `checksum_algorithm` - The checksum algorithm.
     ///
     /// ## Examples
     ///
     /// ```rust,no_run
     /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
-    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb_migrations::{ChecksumAlgorithm, MigrationRunner};
     /// use surrealdb::engine::any::connect;
     /// use surrealdb::opt::auth::Root;
     ///
@@ -650,32 +817,37 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// MigrationRunner::new(&db)
-    ///     .reset()
-    ///     .await
-    ///     .expect("Failed to revert migrations");
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_checksum_algorithm(ChecksumAlgorithm::Normalized) // Ignore formatting and comments
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn reset(&self) -> Result<()> {
-        let args = ApplyArgs {
-            operation: ApplyOperation::Reset,
+    pub fn with_checksum_algorithm(self, checksum_algorithm: ChecksumAlgorithm) -> Self {
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
-            display_logs: false,
-            dry_run: false,
-            validate_checksum: false,
-            validate_version_order: false,
+            source: self.source,
             config_file: self.config_file,
-            output: false,
-            tags: self.tags.clone(),
-            exclude_tags: self.exclude_tags.clone(),
-        };
-        apply::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: Some(checksum_algorithm),
+            out_of_order: self.out_of_order,
+            executor: self.executor,
+        }
     }
 
-    /// Re-apply an already applied migration script.
+    /// Explicitly apply migrations older than the last migration applied.
+    /// By default, the value defined in the configuration file is used, or `false` if none.
+    ///
+    /// Without this mode, such migrations are still applied but a warning is emitted for each of them,
+    /// and they are rejected when validating the version order (see `validate_version_order`).
+    /// In both cases, they are reported as applied out of order.
     ///
     /// ## Examples
     ///
@@ -698,30 +870,38 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// MigrationRunner::new(&db)
-    ///     .redo("20240607_163541_AddPost")
-    ///     .await
-    ///     .expect("Failed to re-apply migration");
+    /// let report = MigrationRunner::new(&db)
+    ///     .allow_out_of_order()
+    ///     .up()
+    ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn redo(&self, migration_script: &str) -> Result<()> {
-        let args = RedoArgs {
-            migration_script: migration_script.to_string(),
+    pub fn allow_out_of_order(self) -> Self {
+        MigrationRunner {
             db: self.db,
-            dir: self.dir,
-            display_logs: false,
-            dry_run: false,
-            validate_checksum: false,
-            validate_version_order: false,
+            source: self.source,
             config_file: self.config_file,
-            output: false,
-        };
-        redo::main(args).await
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: Some(true),
+            executor: self.executor,
+        }
     }
 
-    /// List script migrations that have been applied to the database.
+    /// Set the name of the person or service applying the migrations, stored in the migrations history.
+    /// By default, it will try to read it from the configuration file (`[core] executor`),
+    /// or use the name of the user running the process.
+    ///
+    /// ## Arguments
+    ///
+    /// * `executor` - Name of the executor.
     ///
     /// ## Examples
     ///
@@ -744,14 +924,1124 @@ impl<'a, C: Connection> MigrationRunner<'a, C> {
     /// // Select a specific namespace / database
     /// db.use_ns("namespace").use_db("database").await?;
     ///
-    /// let migrations_applied = MigrationRunner::new(&db)
-    ///     .list()
+    /// let runner = MigrationRunner::new(&db)
+    ///     .with_executor("deploy-pipeline")
+    ///     .up()
     ///     .await?;
     ///
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn list(&self) -> Result<Vec<ScriptMigration>> {
-        surrealdb::list_script_migration_ordered_by_execution_date(self.db).await
+    pub fn with_executor(self, executor: &str) -> Self {
+        MigrationRunner {
+            db: self.db,
+            source: self.source,
+            config_file: self.config_file,
+            history_table: self.history_table,
+            tags: self.tags,
+            exclude_tags: self.exclude_tags,
+            observer: self.observer,
+            code_migrations: self.code_migrations,
+            lock: self.lock,
+            checksum_algorithm: self.checksum_algorithm,
+            out_of_order: self.out_of_order,
+            executor: Some(executor.to_string()),
+        }
+    }
+
+    /// Validate the version order of the migrations so that you cannot run migrations if there are
+    /// gaps in the migrations history.
+    /// In out-of-order mode (see `allow_out_of_order`), the migrations older than the last migration applied
+    /// are accepted and reported instead.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let runner = MigrationRunner::new(&db);
+    ///
+    /// runner.validate_version_order().await?;
+    /// runner.up().await?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn validate_version_order(&self) -> Result<(), MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ValidateVersionOrderArgs {
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            out_of_order: self.get_out_of_order()?,
+        };
+        validate_version_order::main(args)
+            .await
+            .map_err(MigrationError::from)
+    }
+
+    /// Validate the checksum of the migrations already played so that you cannot run migrations if there are
+    /// changes detected in the migrations directory.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let runner = MigrationRunner::new(&db);
+    ///
+    /// runner.validate_checksum().await?;
+    /// runner.up().await?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn validate_checksum(&self) -> Result<(), MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ValidateChecksumArgs {
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+        };
+        validate_checksum::main(args)
+            .await
+            .map_err(MigrationError::from)
+    }
+
+    /// Apply schema definitions and apply all migrations.
+    ///
+    /// Returns an [`ApplyReport`] describing every migration executed during the run.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let report = MigrationRunner::new(&db)
+    ///     .up()
+    ///     .await
+    ///     .expect("Failed to apply migrations");
+    ///
+    /// for migration in &report.migrations {
+    ///     println!("{migration}");
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn up(&self) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args: ApplyArgs<C> = ApplyArgs {
+            operation: ApplyOperation::Up,
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: self.observer,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: self.lock,
+            single_transaction: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Apply schema definitions and all pending migrations in a single transaction.
+    /// If any statement fails, the whole batch is cancelled and no migration is applied.
+    ///
+    /// Code migrations and non-transactional migrations (`.notx.surql`) cannot be applied this way.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .up_atomic()
+    ///     .await
+    ///     .expect("Failed to apply migrations");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn up_atomic(&self) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ApplyArgs {
+            operation: ApplyOperation::Up,
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: self.observer,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: self.lock,
+            single_transaction: true,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Apply schema definitions and all migrations up to and including the named migration.
+    ///
+    /// ## Arguments
+    ///
+    /// * `name` - This parameter allows you to skip ulterior migrations.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .up_to("20230101_120002_AddPost")
+    ///     .await
+    ///     .expect("Failed to apply migrations");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn up_to(&self, name: &str) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ApplyArgs {
+            operation: ApplyOperation::UpTo(name.to_string()),
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: self.observer,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: self.lock,
+            single_transaction: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Apply only the next migration. This allows you to apply migrations step by step when necessary.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .up_single()
+    ///     .await
+    ///     .expect("Failed to apply migration");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn up_single(&self) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ApplyArgs {
+            operation: ApplyOperation::UpSingle,
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: self.observer,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: self.lock,
+            single_transaction: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Revert schema definitions and all migrations down to the named migration.
+    ///
+    /// ## Arguments
+    ///
+    /// * `name` - This parameter allows you to revert applied migrations to this one.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .down_to("20240607_163541_AddPost")
+    ///     .await
+    ///     .expect("Failed to revert migrations");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn down_to(&self, name: &str) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ApplyArgs {
+            operation: ApplyOperation::DownTo(name.to_string()),
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: self.observer,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: self.lock,
+            single_transaction: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Revert the last applied migration. This allows you to downgrade migrations step by step when necessary.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .down_single()
+    ///     .await
+    ///     .expect("Failed to revert migration");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn down_single(&self) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ApplyArgs {
+            operation: ApplyOperation::DownSingle,
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: self.observer,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: self.lock,
+            single_transaction: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Revert ALL schema definitions and migrations.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .reset()
+    ///     .await
+    ///     .expect("Failed to revert migrations");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn reset(&self) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ApplyArgs {
+            operation: ApplyOperation::Reset,
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: self.observer,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: self.lock,
+            single_transaction: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Re-apply an already applied migration script.
+    /// The checksum stored in the history is updated to the content of the re-applied file.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .redo("20240607_163541_AddPost")
+    ///     .await
+    ///     .expect("Failed to re-apply migration");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn redo(&self, migration_script: &str) -> Result<ApplyReport, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = RedoArgs {
+            migration_script: migration_script.to_string(),
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            code_migrations: &self.code_migrations,
+            observer: self.observer,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            output: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            lock: self.lock,
+        };
+        redo::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Mark all migrations up to and including the given migration as applied, without executing them.
+    /// Useful to adopt migrations on a database that already contains the corresponding schema and data.
+    ///
+    /// Fails if the migration history is not empty, see [`MigrationRunner::force_baseline`] to bypass this check.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .baseline("20240607_163541_AddPost")
+    ///     .await
+    ///     .expect("Failed to baseline migrations");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn baseline(&self, migration_name: &str) -> Result<Vec<String>, MigrationError> {
+        self.run_baseline(migration_name, false).await
+    }
+
+    /// Mark all migrations up to and including the given migration as applied, without executing them,
+    /// even if the migration history is not empty.
+    /// Migrations already applied are left untouched.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .force_baseline("20240607_163541_AddPost")
+    ///     .await
+    ///     .expect("Failed to baseline migrations");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn force_baseline(
+        &self,
+        migration_name: &str,
+    ) -> Result<Vec<String>, MigrationError> {
+        self.run_baseline(migration_name, true).await
+    }
+
+    async fn run_baseline(
+        &self,
+        migration_name: &str,
+        force: bool,
+    ) -> Result<Vec<String>, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = BaselineArgs {
+            migration_name: migration_name.to_string(),
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            code_migrations: &self.code_migrations,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            force,
+            lock: self.lock,
+        };
+        baseline::main(args).await.map_err(MigrationError::from)
+    }
+
+    /// Mark a single migration as applied, without executing it.
+    /// Useful to record a change that has been applied manually.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .fake("20240607_163541_AddPost")
+    ///     .await
+    ///     .expect("Failed to mark migration as applied");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fake(&self, migration_name: &str) -> Result<(), MigrationError> {
+        self.run_fake(FakeOperation::Fake, migration_name).await
+    }
+
+    /// Remove a single migration from the migrations history, without reverting it.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// MigrationRunner::new(&db)
+    ///     .unfake("20240607_163541_AddPost")
+    ///     .await
+    ///     .expect("Failed to remove migration from history");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn unfake(&self, migration_name: &str) -> Result<(), MigrationError> {
+        self.run_fake(FakeOperation::Unfake, migration_name).await
+    }
+
+    async fn run_fake(
+        &self,
+        operation: FakeOperation,
+        migration_name: &str,
+    ) -> Result<(), MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = FakeArgs {
+            operation,
+            migration_name: migration_name.to_string(),
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            code_migrations: &self.code_migrations,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            lock: self.lock,
+        };
+        fake::main(args).await.map_err(MigrationError::from)?;
+
+        Ok(())
+    }
+
+    /// List script migrations that have been applied to the database.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let migrations_applied = MigrationRunner::new(&db)
+    ///     .list()
+    ///     .await?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list(&self) -> Result<Vec<ScriptMigration>, MigrationError> {
+        let history_table = self.get_history_table();
+
+        surrealdb::list_script_migration_ordered_by_execution_date(self.db, &history_table)
+            .await
+            .map_err(MigrationError::from)
+    }
+
+    /// List the events recorded for every action applied to the migrations
+    /// (up, down, redo, baseline, fake, unfake and repair), ordered by execution date.
+    /// Failed actions are also recorded, along with their error message.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let events = MigrationRunner::new(&db)
+    ///     .history()
+    ///     .await?;
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn history(&self) -> Result<Vec<MigrationEvent>, MigrationError> {
+        let history_table = self.get_history_table();
+
+        migration_event::list_migration_events(self.db, &history_table)
+            .await
+            .map_err(MigrationError::from)
+    }
+
+    /// Compute the steps that would be executed by an apply operation, without applying them.
+    /// The database is only read to retrieve the migrations already applied.
+    ///
+    /// ## Arguments
+    ///
+    /// * `operation` - The operation to plan, e.g. `ApplyOperation::Up`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::{ApplyOperation, MigrationRunner};
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let plan = MigrationRunner::new(&db)
+    ///     .plan(ApplyOperation::Up)
+    ///     .await?;
+    ///
+    /// for step in plan.steps {
+    ///     println!("{}", step.to_surql());
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan(&self, operation: ApplyOperation) -> Result<MigrationPlan, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = ApplyArgs {
+            operation,
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            observer: None,
+            code_migrations: &self.code_migrations,
+            dry_run: false,
+            validate_checksum: false,
+            validate_version_order: false,
+            config_file: self.config_file,
+            output: false,
+            tags: self.tags.clone(),
+            exclude_tags: self.exclude_tags.clone(),
+            lock: None,
+            single_transaction: false,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            executor: self.get_executor(),
+            out_of_order: self.get_out_of_order()?,
+        };
+        apply::plan(args).await.map_err(MigrationError::from)
+    }
+
+    /// Compare the migrations applied to the database with the migration files,
+    /// like the `status` command does.
+    ///
+    /// A database without history table is reported as having every migration pending.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let status = MigrationRunner::new(&db)
+    ///     .status()
+    ///     .await?;
+    ///
+    /// if !status.is_up_to_date() {
+    ///     println!("{} migration(s) left to apply", status.pending.len());
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status(&self) -> Result<MigrationStatus, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = MigrationStatusArgs {
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            code_migrations: &self.code_migrations,
+        };
+        migration_status::main(args)
+            .await
+            .map_err(MigrationError::from)
+    }
+
+    /// Realign the migrations history with the migration files, like the `repair` command does.
+    ///
+    /// Rewrites the stored checksums that do not match the migration files anymore,
+    /// backfills the checksums of migrations applied before the `checksum` field existed
+    /// and removes the history records of migration files that have been deleted.
+    /// Records without checksum and without file are kept, as they can be code migrations.
+    ///
+    /// See [`MigrationRunner::preview_repair`] to list the changes without applying them.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let repair = MigrationRunner::new(&db)
+    ///     .repair()
+    ///     .await?;
+    ///
+    /// for migration in &repair.checksum_mismatches {
+    ///     println!("Checksum updated for migration '{migration}'");
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn repair(&self) -> Result<MigrationRepair, MigrationError> {
+        self.run_repair(false).await
+    }
+
+    /// List the changes that [`MigrationRunner::repair`] would make to the migrations history,
+    /// without applying them.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use color_eyre::eyre::{eyre, ContextCompat, Result, WrapErr};
+    /// use surrealdb_migrations::MigrationRunner;
+    /// use surrealdb::engine::any::connect;
+    /// use surrealdb::opt::auth::Root;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let db = connect("ws://localhost:8000").await?;
+    ///
+    /// // Signin as a namespace, database, or root user
+    /// db.signin(Root {
+    ///     username: "root",
+    ///     password: "root",
+    /// }).await?;
+    ///
+    /// // Select a specific namespace / database
+    /// db.use_ns("namespace").use_db("database").await?;
+    ///
+    /// let runner = MigrationRunner::new(&db);
+    ///
+    /// let repair = runner.preview_repair().await?;
+    ///
+    /// if repair.missing_files.is_empty() {
+    ///     runner.repair().await?;
+    /// }
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn preview_repair(&self) -> Result<MigrationRepair, MigrationError> {
+        self.run_repair(true).await
+    }
+
+    async fn run_repair(&self, dry_run: bool) -> Result<MigrationRepair, MigrationError> {
+        let filesystem_source = FileSystemSource::from_config_file(self.config_file);
+        let history_table = self.get_history_table();
+
+        let args = MigrationRepairArgs {
+            db: self.db,
+            source: self.source.as_deref().unwrap_or(&filesystem_source),
+            history_table: &history_table,
+            code_migrations: &self.code_migrations,
+            checksum_algorithm: self.get_checksum_algorithm()?,
+            dry_run,
+            executor: self.get_executor(),
+            lock: self.lock,
+        };
+        migration_repair::main(args)
+            .await
+            .map_err(MigrationError::from)
+    }
+
+    fn get_history_table(&self) -> String {
+        match &self.history_table {
+            Some(history_table) => history_table.to_string(),
+            None => config::retrieve_history_table(self.config_file),
+        }
+    }
+
+    fn get_checksum_algorithm(&self) -> Result<ChecksumAlgorithm, MigrationError> {
+        match self.checksum_algorithm {
+            Some(checksum_algorithm) => Ok(checksum_algorithm),
+            None => {
+                config::retrieve_checksum_algorithm(self.config_file).map_err(MigrationError::from)
+            }
+        }
+    }
+
+    fn get_out_of_order(&self) -> Result<bool, MigrationError> {
+        match self.out_of_order {
+            Some(out_of_order) => Ok(out_of_order),
+            None => config::retrieve_out_of_order(self.config_file).map_err(MigrationError::from),
+        }
+    }
+
+    fn get_executor(&self) -> Option<String> {
+        match &self.executor {
+            Some(executor) => Some(executor.to_string()),
+            None => config::retrieve_executor(self.config_file),
+        }
     }
 }
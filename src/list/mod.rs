@@ -3,15 +3,17 @@ pub mod args;
 pub use args::ListArgs;
 use chrono::{DateTime, Utc};
 use chrono_human_duration::ChronoHumanDuration;
-use cli_table::{format::Border, Cell, ColorChoice, Style, Table};
+use cli_table::{Cell, ColorChoice, Style, Table, format::Border};
 use color_eyre::eyre::Result;
 
 use crate::{
-    common::get_migration_display_name, constants::SURQL_FILE_EXTENSION,
+    common::get_migration_display_name, config, constants::SURQL_FILE_EXTENSION,
     runbin::surrealdb::create_surrealdb_client,
     surrealdb::list_script_migration_ordered_by_execution_date,
 };
 
+const NOT_AVAILABLE: &str = "N/A";
+
 pub async fn main(args: ListArgs<'_>) -> Result<()> {
     let ListArgs {
         db_configuration,
@@ -21,7 +23,10 @@ pub async fn main(args: ListArgs<'_>) -> Result<()> {
 
     let client = create_surrealdb_client(config_file, &db_configuration).await?;
 
-    let migrations_applied = list_script_migration_ordered_by_execution_date(&client).await?;
+    let history_table = config::retrieve_history_table(config_file);
+
+    let migrations_applied =
+        list_script_migration_ordered_by_execution_date(&client, &history_table).await?;
 
     if migrations_applied.is_empty() {
         println!("No migrations applied yet!");
@@ -38,12 +43,33 @@ pub async fn main(args: ListArgs<'_>) -> Result<()> {
                         let since = now.signed_duration_since(executed_at);
                         since.format_human().to_string()
                     }
-                    Err(_) => "N/A".to_string(),
+                    Err(_) => NOT_AVAILABLE.to_string(),
+                };
+
+                let executed_by = m.executed_by.as_deref().unwrap_or(NOT_AVAILABLE);
+                let duration = match m.duration_ms {
+                    Some(duration_ms) => format!("{duration_ms}ms"),
+                    None => NOT_AVAILABLE.to_string(),
+                };
+                let tool_version = m.tool_version.as_deref().unwrap_or(NOT_AVAILABLE);
+                let tags = match &m.tags {
+                    Some(tags) => tags.join(", "),
+                    None => NOT_AVAILABLE.to_string(),
                 };
+                let run_id = m.run_id.as_deref().unwrap_or(NOT_AVAILABLE);
 
                 let file_name = m.script_name.clone() + SURQL_FILE_EXTENSION;
 
-                vec![display_name.cell(), since.cell(), file_name.cell()]
+                vec![
+                    display_name.cell(),
+                    since.cell(),
+                    executed_by.cell(),
+                    duration.cell(),
+                    tool_version.cell(),
+                    tags.cell(),
+                    run_id.cell(),
+                    file_name.cell(),
+                ]
             })
             .collect::<Vec<_>>();
 
@@ -58,6 +84,11 @@ pub async fn main(args: ListArgs<'_>) -> Result<()> {
             .title(vec![
                 "Name".cell().bold(true),
                 "Executed at".cell().bold(true),
+                "Executed by".cell().bold(true),
+                "Duration".cell().bold(true),
+                "Version".cell().bold(true),
+                "Tags".cell().bold(true),
+                "Run id".cell().bold(true),
                 "File name".cell().bold(true),
             ])
             .color_choice(color_choice)
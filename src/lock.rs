@@ -0,0 +1,230 @@
+use ::surrealdb::{Connection, Surreal, sql::Uuid};
+use color_eyre::eyre::{ContextCompat, Result};
+use itertools::Itertools;
+use serde::Deserialize;
+use std::{
+    future::Future,
+    pin::pin,
+    time::{Duration, Instant},
+};
+use tracing::{debug, info, warn};
+
+use crate::{constants::MIGRATION_LOCK_TABLE_NAME, error::MigrationError};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MIN_RENEW_INTERVAL: Duration = Duration::from_millis(100);
+
+const RECORD_ALREADY_EXISTS_ERROR: &str = "already exists";
+const FAILED_TRANSACTION_ERROR: &str = "The query was not executed due to a failed transaction";
+
+/// Options of the lock acquired before applying migrations.
+#[derive(Debug, Clone, Copy)]
+pub struct LockOptions {
+    /// Maximum time to wait for a lock held by another migration run.
+    pub wait_timeout: Duration,
+    /// Time after which a lock is considered stale and can be taken over.
+    /// The lease is renewed while the migration run holds the lock.
+    pub lease: Duration,
+}
+
+impl Default for LockOptions {
+    fn default() -> Self {
+        LockOptions {
+            wait_timeout: Duration::from_secs(60),
+            lease: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MigrationLockRecord {
+    owner: String,
+    expires_at: String,
+}
+
+/// An advisory lock stored in the `migration_lock` table, one record per history table.
+pub struct MigrationLock<'a, C: Connection> {
+    client: &'a Surreal<C>,
+    history_table: String,
+    owner: String,
+    lease: Duration,
+}
+
+/// Run an operation while holding the migration lock, or without lock if `options` is `None`.
+pub async fn run_with_lock<C: Connection, T>(
+    client: &Surreal<C>,
+    history_table: &str,
+    options: Option<LockOptions>,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let Some(options) = options else {
+        return operation.await;
+    };
+
+    let lock = acquire(client, history_table, options).await?;
+
+    let result = lock.hold(operation).await;
+
+    let release_result = lock.release().await;
+
+    // the error of the operation prevails over a failure to release the lock
+    if result.is_ok() {
+        release_result?;
+    }
+
+    result
+}
+
+pub async fn acquire<'a, C: Connection>(
+    client: &'a Surreal<C>,
+    history_table: &str,
+    options: LockOptions,
+) -> Result<MigrationLock<'a, C>> {
+    let owner = Uuid::new_v4().to_string();
+    let lease = ::surrealdb::sql::Duration::from(options.lease);
+    let deadline = Instant::now() + options.wait_timeout;
+
+    // a stale lock (lease expired) is removed before trying to create our own lock,
+    // in the same transaction so that a single migration run can take it over
+    let query = format!(
+        "BEGIN TRANSACTION;
+DELETE type::thing($table, $id) WHERE expires_at < time::now();
+CREATE type::thing($table, $id) SET owner = $owner, acquired_at = time::now(), expires_at = time::now() + {lease};
+COMMIT TRANSACTION;"
+    );
+
+    let mut is_waiting = false;
+
+    loop {
+        let errors = client
+            .query(&query)
+            .bind(("table", MIGRATION_LOCK_TABLE_NAME))
+            .bind(("id", history_table.to_string()))
+            .bind(("owner", owner.to_string()))
+            .await?
+            .take_errors();
+
+        if errors.is_empty() {
+            debug!(owner = %owner, "Migration lock acquired");
+
+            return Ok(MigrationLock {
+                client,
+                history_table: history_table.to_string(),
+                owner,
+                lease: options.lease,
+            });
+        }
+
+        let is_lock_held = errors
+            .values()
+            .any(|error| error.to_string().contains(RECORD_ALREADY_EXISTS_ERROR));
+
+        if !is_lock_held {
+            let error = errors
+                .into_iter()
+                .sorted_by_key(|(index, _)| *index)
+                .map(|(_, error)| error)
+                .find(|error| error.to_string() != FAILED_TRANSACTION_ERROR)
+                .context("Failed to acquire the migration lock")?;
+
+            return Err(error.into());
+        }
+
+        // 💡 the lock can be released between our attempt and this query, it is then retried right away
+        let Some(current_lock) = get_current_lock(client, history_table).await? else {
+            continue;
+        };
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(MigrationError::LockTimeout {
+                owner: current_lock.owner,
+                expires_at: current_lock.expires_at,
+            }
+            .into());
+        }
+
+        if !is_waiting {
+            info!(
+                owner = %current_lock.owner,
+                "Waiting for the migration lock held by another migration run..."
+            );
+            is_waiting = true;
+        }
+
+        tokio::time::sleep(POLL_INTERVAL.min(deadline - now)).await;
+    }
+}
+
+async fn get_current_lock<C: Connection>(
+    client: &Surreal<C>,
+    history_table: &str,
+) -> Result<Option<MigrationLockRecord>> {
+    let mut response = client
+        .query("SELECT * FROM ONLY type::thing($table, $id)")
+        .bind(("table", MIGRATION_LOCK_TABLE_NAME))
+        .bind(("id", history_table.to_string()))
+        .await?
+        .check()?;
+
+    let current_lock: Option<MigrationLockRecord> = response.take(0)?;
+    Ok(current_lock)
+}
+
+impl<C: Connection> MigrationLock<'_, C> {
+    /// Run an operation, renewing the lease of the lock until the operation completes.
+    async fn hold<T>(&self, operation: impl Future<Output = Result<T>>) -> Result<T> {
+        let mut operation = pin!(operation);
+
+        // 💡 the lease is renewed well before it expires so that a long migration run keeps the lock
+        let renew_interval = (self.lease / 3).max(MIN_RENEW_INTERVAL);
+        let mut renew_interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + renew_interval, renew_interval);
+
+        loop {
+            tokio::select! {
+                result = &mut operation => return result,
+                _ = renew_interval.tick() => match self.renew().await {
+                    Ok(true) => debug!(owner = %self.owner, "Migration lock renewed"),
+                    Ok(false) => return Err(MigrationError::LockLost.into()),
+                    // a transient failure is retried on the next tick, before the lease expires
+                    Err(error) => warn!(error = %error, "Failed to renew the migration lock"),
+                },
+            }
+        }
+    }
+
+    /// Extend the lease of the lock, returns `false` if the lock is not held anymore.
+    async fn renew(&self) -> Result<bool> {
+        let lease = ::surrealdb::sql::Duration::from(self.lease);
+
+        let mut response = self
+            .client
+            .query(format!(
+                "UPDATE type::thing($table, $id) SET expires_at = time::now() + {lease} WHERE owner = $owner;"
+            ))
+            .bind(("table", MIGRATION_LOCK_TABLE_NAME))
+            .bind(("id", self.history_table.to_string()))
+            .bind(("owner", self.owner.to_string()))
+            .await?
+            .check()?;
+
+        let renewed_locks: Vec<MigrationLockRecord> = response.take(0)?;
+        Ok(!renewed_locks.is_empty())
+    }
+
+    pub async fn release(self) -> Result<()> {
+        // 💡 only our own lock is removed, the lock of another migration run is left untouched
+        self.client
+            .query("DELETE type::thing($table, $id) WHERE owner = $owner;")
+            .bind(("table", MIGRATION_LOCK_TABLE_NAME))
+            .bind(("id", self.history_table))
+            .bind(("owner", self.owner.to_string()))
+            .await?
+            .check()?;
+
+        debug!(owner = %self.owner, "Migration lock released");
+
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use apply::ApplyArgs;
+use baseline::BaselineArgs;
 #[cfg(feature = "branching")]
 use branch::args::BranchArgs;
 use clap::Parser;
@@ -8,38 +9,65 @@ use color_eyre::config::Theme;
 use color_eyre::eyre::Result;
 use create::CreateArgs;
 use diff::DiffArgs;
+use fake::{FakeArgs, FakeOperation};
+use history::HistoryArgs;
 use input::SurrealdbConfiguration;
 use list::ListArgs;
+use lock::LockOptions;
 use models::ApplyOperation;
 use redo::RedoArgs;
+use repair::RepairArgs;
 use runbin::config::retrieve_exclude_tags;
 use runbin::config::retrieve_tags;
+use runbin::logs::ConsoleLayer;
 use runbin::surrealdb::create_surrealdb_client;
 #[cfg(feature = "scaffold")]
 use scaffold::args::ScaffoldArgs;
+use source::FileSystemSource;
 use status::StatusArgs;
 use std::collections::HashSet;
 use std::env;
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
 
 mod apply;
+mod baseline;
 #[cfg(feature = "branching")]
 mod branch;
 mod cli;
+mod code_migration;
 mod common;
 mod config;
 mod constants;
 mod create;
 mod diff;
+mod error;
+mod fake;
 mod file;
+mod history;
 mod input;
 mod io;
 mod list;
+mod lock;
+// the in-memory source is only used by the unit tests of the CLI
+#[cfg(test)]
+#[path = "source/memory.rs"]
+mod memory_source;
+mod migration_event;
+mod migration_repair;
+mod migration_status;
 mod models;
+mod observer;
 mod redo;
 mod remove;
+mod repair;
+mod rollback;
 mod runbin;
 #[cfg(feature = "scaffold")]
 mod scaffold;
+mod schema_snapshot;
+mod script_migration;
+mod source;
 mod status;
 mod surrealdb;
 mod tags;
@@ -67,6 +95,8 @@ async fn sub_main() -> Result<()> {
         color_eyre::install()?;
     }
 
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(ConsoleLayer))?;
+
     let args = Args::parse();
 
     let config_file = args.config_file.as_deref();
@@ -89,6 +119,10 @@ async fn sub_main() -> Result<()> {
                 down,
                 reset,
                 redo,
+                baseline,
+                force,
+                fake,
+                unfake,
                 address,
                 ns,
                 db,
@@ -97,10 +131,15 @@ async fn sub_main() -> Result<()> {
                 dry_run,
                 validate_checksum,
                 validate_version_order,
+                out_of_order,
                 output,
+                plan,
+                single_transaction,
                 tags,
                 exclude_tags,
-            } = apply_args;
+                no_lock,
+                lock_timeout,
+            } = *apply_args;
 
             let db_configuration = SurrealdbConfiguration {
                 address,
@@ -111,19 +150,100 @@ async fn sub_main() -> Result<()> {
             };
             let db = create_surrealdb_client(config_file, &db_configuration).await?;
 
-            if let Some(redo) = redo {
+            let source = FileSystemSource::from_config_file(config_file);
+            let history_table = config::retrieve_history_table(config_file);
+            let checksum_algorithm = config::retrieve_checksum_algorithm(config_file)?;
+            let executor = config::retrieve_executor(config_file);
+            let lock = match no_lock {
+                true => None,
+                false => {
+                    let default_lock_options = LockOptions::default();
+                    Some(LockOptions {
+                        wait_timeout: lock_timeout
+                            .map(Duration::from_secs)
+                            .unwrap_or(default_lock_options.wait_timeout),
+                        ..default_lock_options
+                    })
+                }
+            };
+
+            if let Some(baseline) = baseline {
+                let args = BaselineArgs {
+                    migration_name: baseline,
+                    db: &db,
+                    source: &source,
+                    history_table: &history_table,
+                    code_migrations: &[],
+                    checksum_algorithm,
+                    executor,
+                    force,
+                    lock,
+                };
+                let migrations_baselined = baseline::main(args).await?;
+
+                println!(
+                    "{} migration(s) marked as applied!",
+                    migrations_baselined.len()
+                );
+
+                Ok(())
+            } else if let Some(fake) = fake {
+                let args = FakeArgs {
+                    operation: FakeOperation::Fake,
+                    migration_name: fake,
+                    db: &db,
+                    source: &source,
+                    history_table: &history_table,
+                    code_migrations: &[],
+                    checksum_algorithm,
+                    executor,
+                    lock,
+                };
+                let migration_name = fake::main(args).await?;
+
+                println!("Migration '{migration_name}' marked as applied!");
+
+                Ok(())
+            } else if let Some(unfake) = unfake {
+                let args = FakeArgs {
+                    operation: FakeOperation::Unfake,
+                    migration_name: unfake,
+                    db: &db,
+                    source: &source,
+                    history_table: &history_table,
+                    code_migrations: &[],
+                    checksum_algorithm,
+                    executor,
+                    lock,
+                };
+                let migration_name = fake::main(args).await?;
+
+                println!("Migration '{migration_name}' removed from the migrations history!");
+
+                Ok(())
+            } else if let Some(redo) = redo {
                 let args = RedoArgs {
                     migration_script: redo,
                     db: &db,
-                    dir: None,
-                    display_logs: true,
+                    source: &source,
+                    history_table: &history_table,
+                    code_migrations: &[],
+                    observer: None,
                     dry_run,
                     validate_checksum,
                     validate_version_order,
-                    config_file,
                     output,
+                    checksum_algorithm,
+                    executor,
+                    lock,
                 };
-                redo::main(args).await
+                let report = redo::main(args).await?;
+
+                if !report.dry_run {
+                    println!("Migration successfully re-executed!");
+                }
+
+                Ok(())
             } else {
                 let operation = ApplyOperation::try_from(up, down, reset)?;
                 let tags = tags
@@ -136,8 +256,10 @@ async fn sub_main() -> Result<()> {
                 let args = ApplyArgs {
                     operation,
                     db: &db,
-                    dir: None,
-                    display_logs: true,
+                    source: &source,
+                    history_table: &history_table,
+                    observer: None,
+                    code_migrations: &[],
                     dry_run,
                     validate_checksum,
                     validate_version_order,
@@ -145,14 +267,38 @@ async fn sub_main() -> Result<()> {
                     output,
                     tags,
                     exclude_tags,
+                    lock,
+                    single_transaction,
+                    checksum_algorithm,
+                    out_of_order: out_of_order || config::retrieve_out_of_order(config_file)?,
+                    executor,
                 };
-                apply::main(args).await
+                if plan {
+                    let plan = apply::plan(args).await?;
+                    print!("{plan}");
+
+                    return Ok(());
+                }
+
+                let report = apply::main(args).await?;
+
+                if !report.dry_run {
+                    print!("{report}");
+                }
+
+                Ok(())
             }
         }
         Action::List(list_args) => list::main(ListArgs::from(list_args, config_file)).await,
         Action::Status(status_args) => {
             status::main(StatusArgs::from(status_args, config_file)).await
         }
+        Action::Repair(repair_args) => {
+            repair::main(RepairArgs::from(repair_args, config_file)).await
+        }
+        Action::History(history_args) => {
+            history::main(HistoryArgs::from(history_args, config_file)).await
+        }
         #[cfg(feature = "branching")]
         Action::Branch(branch_args) => {
             let args = BranchArgs::try_from(branch_args, config_file)?;
@@ -165,6 +311,14 @@ async fn sub_main() -> Result<()> {
                 BranchArgs::Status(args) => branch::status::main(args).await,
             }
         }
-        Action::Diff(diff_args) => diff::main(DiffArgs::from(diff_args, config_file)).await,
+        Action::Diff(diff_args) => {
+            let exit_code = diff_args.exit_code;
+            let result = diff::main(DiffArgs::from(diff_args, config_file)).await;
+
+            match exit_code {
+                true => diff::exit_with_status(result),
+                false => result.map(|_| ()),
+            }
+        }
     }
 }
@@ -0,0 +1,164 @@
+use ::surrealdb::{
+    Connection, Surreal,
+    sql::{
+        Data, Function, Idiom, Operator, Output, Permission, Permissions, Statement, Value, Values,
+        statements::{CreateStatement, DefineStatement, DefineTableStatement},
+    },
+};
+use color_eyre::eyre::{Report, Result, WrapErr};
+
+use crate::{
+    models::{MigrationEvent, MigrationEventAction},
+    script_migration::RunMetadata,
+    surrealdb::{self, TransactionAction},
+};
+
+/// An action to record in the migration event log.
+pub struct NewMigrationEvent {
+    pub action: MigrationEventAction,
+    pub script_name: String,
+    /// The error message, if the action failed.
+    pub error: Option<String>,
+}
+
+impl NewMigrationEvent {
+    pub fn from_result(
+        action: MigrationEventAction,
+        script_name: &str,
+        result: &Result<(), Report>,
+    ) -> Self {
+        NewMigrationEvent {
+            action,
+            script_name: script_name.to_string(),
+            error: result.as_ref().err().map(|error| error.to_string()),
+        }
+    }
+}
+
+/// The event log is stored next to the history table, e.g. `script_migration_event`.
+pub fn get_event_table_name(history_table: &str) -> String {
+    format!("{history_table}_event")
+}
+
+/// Appends events to the migration event log.
+/// The actions recorded are already executed when the log cannot be written,
+/// so callers return this error only once the error of the action itself is handled.
+pub async fn record_migration_events<C: Connection>(
+    client: &Surreal<C>,
+    history_table: &str,
+    run_metadata: &RunMetadata,
+    events: Vec<NewMigrationEvent>,
+) -> Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let event_table = get_event_table_name(history_table);
+
+    let statements = [define_event_table_statement(&event_table)]
+        .into_iter()
+        .chain(
+            events
+                .iter()
+                .map(|event| create_event_statement(&event_table, run_metadata, event)),
+        )
+        .collect();
+
+    surrealdb::apply_in_transaction(client, statements, TransactionAction::Commit, None)
+        .await
+        .wrap_err("Failed to record the migration events")
+}
+
+pub async fn list_migration_events<C: Connection>(
+    client: &Surreal<C>,
+    history_table: &str,
+) -> Result<Vec<MigrationEvent>> {
+    let event_table = get_event_table_name(history_table);
+
+    if !surrealdb::get_surrealdb_table_exists(client, &event_table).await? {
+        return Ok(vec![]);
+    }
+
+    let mut events: Vec<MigrationEvent> = client.select(event_table).await?;
+    events.sort_by_key(|event| event.executed_at.clone());
+
+    Ok(events)
+}
+
+/// Records can only be created by the migration tool, so that the log stays append-only.
+/// Table permissions do not apply to root and namespace users (the CLI usually connects as root),
+/// so the log is only append-only for database and record users.
+fn define_event_table_statement(event_table: &str) -> Statement {
+    let mut permissions = Permissions::none();
+    permissions.select = Permission::Full;
+
+    let mut define_table_statement = DefineTableStatement::default();
+    define_table_statement.name = event_table.into();
+    define_table_statement.permissions = permissions;
+    define_table_statement.if_not_exists = true;
+
+    Statement::Define(DefineStatement::Table(define_table_statement))
+}
+
+fn create_event_statement(
+    event_table: &str,
+    run_metadata: &RunMetadata,
+    event: &NewMigrationEvent,
+) -> Statement {
+    let mut what = Values::default();
+    what.0.push(Value::Table(event_table.into()));
+
+    let outcome = match event.error {
+        Some(_) => "failure",
+        None => "success",
+    };
+
+    let mut set_expressions = vec![
+        (
+            Idiom::from("action"),
+            Operator::Equal,
+            Value::Strand(event.action.as_str().into()),
+        ),
+        (
+            Idiom::from("script_name"),
+            Operator::Equal,
+            Value::Strand(event.script_name.as_str().into()),
+        ),
+        (
+            Idiom::from("outcome"),
+            Operator::Equal,
+            Value::Strand(outcome.into()),
+        ),
+        (
+            Idiom::from("executed_at"),
+            Operator::Equal,
+            Value::Function(Box::new(Function::Normal("time::now".to_string(), vec![]))),
+        ),
+        (
+            Idiom::from("run_id"),
+            Operator::Equal,
+            Value::Strand(run_metadata.run_id.as_str().into()),
+        ),
+    ];
+    if let Some(error) = &event.error {
+        set_expressions.push((
+            Idiom::from("error"),
+            Operator::Equal,
+            Value::Strand(error.as_str().into()),
+        ));
+    }
+    if let Some(executed_by) = &run_metadata.executed_by {
+        set_expressions.push((
+            Idiom::from("executed_by"),
+            Operator::Equal,
+            Value::Strand(executed_by.as_str().into()),
+        ));
+    }
+
+    let mut create_statement = CreateStatement::default();
+    create_statement.what = what;
+    create_statement.data = Some(Data::SetExpression(set_expressions));
+    create_statement.output = Some(Output::None);
+
+    Statement::Create(create_statement)
+}
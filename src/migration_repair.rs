@@ -0,0 +1,171 @@
+use ::surrealdb::{Connection, Surreal};
+use color_eyre::eyre::{Result, eyre};
+use std::collections::HashSet;
+use tracing::warn;
+
+use crate::{
+    code_migration::{NamedCodeMigration, find_code_migration},
+    constants::ALL_TAGS,
+    io,
+    lock::{self, LockOptions},
+    migration_event::{NewMigrationEvent, record_migration_events},
+    models::{ChecksumAlgorithm, MigrationDirection, MigrationEventAction, MigrationRepair},
+    script_migration::{
+        ChecksumSupport, RunMetadata, compute_checksum_for_script_migration,
+        delete_script_migration_statement, update_script_migration_checksum_statement,
+    },
+    source::MigrationSource,
+    surrealdb::{
+        self, TransactionAction, get_surrealdb_table_definition, get_surrealdb_table_exists,
+        list_script_migration_ordered_by_execution_date,
+    },
+};
+
+pub struct MigrationRepairArgs<'a, C: Connection> {
+    pub db: &'a Surreal<C>,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
+    pub code_migrations: &'a [NamedCodeMigration<'a, C>],
+    pub checksum_algorithm: ChecksumAlgorithm,
+    pub dry_run: bool,
+    pub executor: Option<String>,
+    pub lock: Option<LockOptions>,
+}
+
+/// Realigns the migrations history with the migration files:
+/// rewrites mismatching checksums, backfills missing checksums and removes the records of deleted files.
+/// Records without checksum and without file are kept, as they can be code migrations.
+pub async fn main<C: Connection>(args: MigrationRepairArgs<'_, C>) -> Result<MigrationRepair> {
+    let client = args.db;
+    let history_table = args.history_table;
+
+    // 💡 a dry run only reads the migrations history
+    let lock = match args.dry_run {
+        true => None,
+        false => args.lock,
+    };
+
+    lock::run_with_lock(client, history_table, lock, repair(args)).await
+}
+
+async fn repair<C: Connection>(args: MigrationRepairArgs<'_, C>) -> Result<MigrationRepair> {
+    let MigrationRepairArgs {
+        db: client,
+        source,
+        history_table,
+        code_migrations,
+        checksum_algorithm,
+        dry_run,
+        executor,
+        lock: _,
+    } = args;
+
+    if !get_surrealdb_table_exists(client, history_table).await? {
+        return Err(eyre!(
+            "The table '{}' does not exist. Make sure to apply the migrations once before running this command.",
+            history_table
+        ));
+    }
+
+    let script_migration_table_definition =
+        get_surrealdb_table_definition(client, history_table).await?;
+    let checksum_support =
+        ChecksumSupport::from_table_definition(&script_migration_table_definition);
+
+    let tags = HashSet::from([ALL_TAGS.into()]);
+    let exclude_tags = HashSet::new();
+
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags);
+
+    let migrations_applied =
+        list_script_migration_ordered_by_execution_date(client, history_table).await?;
+
+    let mut repair = MigrationRepair::default();
+    let mut statements = Vec::new();
+
+    for migration_applied in migrations_applied {
+        let script_name = migration_applied.script_name;
+
+        if find_code_migration(code_migrations, &script_name).is_some() {
+            continue;
+        }
+
+        let Some(migration_file) = forward_migrations_files
+            .iter()
+            .find(|f| f.name == script_name)
+        else {
+            // 💡 a record without checksum and without file can be a code migration unknown to the caller
+            if migration_applied.checksum.is_none() {
+                warn!(
+                    migration = script_name,
+                    "Migration file not found, the record is kept as it can be a code migration"
+                );
+                continue;
+            }
+
+            statements.push(delete_script_migration_statement(
+                history_table,
+                &script_name,
+            ));
+            repair.missing_files.push(script_name);
+            continue;
+        };
+
+        let content = migration_file.get_content().unwrap_or_default();
+
+        let is_checksum_valid = match &migration_applied.checksum {
+            Some(checksum) => {
+                let file_checksum = compute_checksum_for_script_migration(
+                    &content,
+                    migration_applied.checksum_algorithm.as_deref(),
+                )?;
+                *checksum == file_checksum
+            }
+            None => false,
+        };
+
+        if is_checksum_valid {
+            continue;
+        }
+
+        let Some(checksum) = checksum_support.compute(&content, checksum_algorithm)? else {
+            continue;
+        };
+
+        statements.push(update_script_migration_checksum_statement(
+            history_table,
+            &script_name,
+            &checksum,
+        ));
+
+        match migration_applied.checksum {
+            Some(_) => repair.checksum_mismatches.push(script_name),
+            None => repair.missing_checksums.push(script_name),
+        }
+    }
+
+    if !dry_run && !statements.is_empty() {
+        let result =
+            surrealdb::apply_in_transaction(client, statements, TransactionAction::Commit, None)
+                .await;
+
+        let run_metadata = RunMetadata::new(executor, &tags);
+        let events = repair
+            .checksum_mismatches
+            .iter()
+            .chain(&repair.missing_checksums)
+            .chain(&repair.missing_files)
+            .map(|script_name| {
+                NewMigrationEvent::from_result(MigrationEventAction::Repair, script_name, &result)
+            })
+            .collect();
+        let events_recorded =
+            record_migration_events(client, history_table, &run_metadata, events).await;
+
+        result?;
+        events_recorded?;
+    }
+
+    Ok(repair)
+}
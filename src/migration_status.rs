@@ -0,0 +1,119 @@
+use ::surrealdb::{Connection, Surreal};
+use color_eyre::eyre::Result;
+use std::collections::HashSet;
+
+use crate::{
+    code_migration::{
+        NamedCodeMigration, ensure_code_migrations_names_are_unique, extract_code_migrations_files,
+    },
+    constants::{ALL_TAGS, OLD_TAG},
+    io,
+    models::{MigrationDirection, MigrationStatus},
+    script_migration::get_latest_migration_applied,
+    source::MigrationSource,
+    surrealdb::{
+        get_surrealdb_table_definition, get_surrealdb_table_exists,
+        list_script_migration_ordered_by_execution_date,
+    },
+};
+
+pub struct MigrationStatusArgs<'a, C: Connection> {
+    pub db: &'a Surreal<C>,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
+    pub code_migrations: &'a [NamedCodeMigration<'a, C>],
+}
+
+pub async fn main<C: Connection>(args: MigrationStatusArgs<'_, C>) -> Result<MigrationStatus> {
+    let MigrationStatusArgs {
+        db: client,
+        source,
+        history_table,
+        code_migrations,
+    } = args;
+
+    // 💡 a missing history table means that no migration has been applied yet
+    let history_table_exists = get_surrealdb_table_exists(client, history_table).await?;
+
+    let supports_checksum = match history_table_exists {
+        true => get_surrealdb_table_definition(client, history_table)
+            .await?
+            .fields
+            .contains_key("checksum"),
+        false => false,
+    };
+
+    let tags = HashSet::from([ALL_TAGS.into()]);
+    let exclude_tags = HashSet::new();
+
+    let schemas_files = io::extract_schemas_files(source, &tags, &exclude_tags)
+        .ok()
+        .unwrap_or_default();
+    let events_files = io::extract_events_files(source, &tags, &exclude_tags)
+        .ok()
+        .unwrap_or_default();
+
+    let schema_definitions = io::concat_files_content(&schemas_files);
+    let event_definitions = io::concat_files_content(&events_files);
+
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags);
+
+    let use_traditional_approach = schema_definitions.is_empty()
+        && event_definitions.is_empty()
+        && !forward_migrations_files.is_empty();
+
+    let use_migration_definitions = !use_traditional_approach;
+
+    ensure_code_migrations_names_are_unique(code_migrations, &forward_migrations_files)?;
+
+    let forward_migrations_files = io::get_sorted_migrations_files(
+        forward_migrations_files
+            .into_iter()
+            .chain(extract_code_migrations_files(
+                code_migrations,
+                MigrationDirection::Forward,
+                &tags,
+                &exclude_tags,
+            ))
+            .collect(),
+    );
+
+    let migrations_applied =
+        list_script_migration_ordered_by_execution_date(client, history_table).await?;
+
+    let names_of_migrations_applied = migrations_applied
+        .iter()
+        .map(|m| m.script_name.to_string())
+        .collect::<HashSet<_>>();
+
+    let names_of_migrations_to_apply = forward_migrations_files
+        .iter()
+        .map(|f| f.name.to_string())
+        .collect::<HashSet<_>>();
+
+    // 💡 migrations with the "old" tag are not applied by default, so they are not pending
+    let default_exclude_tags = HashSet::from([OLD_TAG.into()]);
+
+    let pending = forward_migrations_files
+        .iter()
+        .filter(|f| !names_of_migrations_applied.contains(&f.name))
+        .filter(|f| f.filter_by_tags(&tags, &default_exclude_tags))
+        .map(|f| f.name.to_string())
+        .collect::<Vec<_>>();
+
+    let missing = migrations_applied
+        .iter()
+        .filter(|m| !names_of_migrations_to_apply.contains(&m.script_name))
+        .map(|m| m.script_name.to_string())
+        .collect::<Vec<_>>();
+
+    Ok(MigrationStatus {
+        applied: migrations_applied.len(),
+        pending,
+        missing,
+        supports_checksum,
+        use_migration_definitions,
+        last_applied: get_latest_migration_applied(&migrations_applied).cloned(),
+    })
+}
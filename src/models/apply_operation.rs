@@ -1,8 +1,16 @@
+/// The operation to apply, see `MigrationRunner::plan`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApplyOperation {
+    /// Apply all migrations.
     Up,
+    /// Apply the next migration.
     UpSingle,
+    /// Apply migrations up to this migration name.
     UpTo(String),
+    /// Revert all migrations.
     Reset,
+    /// Revert the last applied migration.
     DownSingle,
+    /// Revert migrations down to this migration name.
     DownTo(String),
 }
@@ -0,0 +1,94 @@
+use std::{
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+
+use crate::common::get_migration_display_name;
+
+use super::MigrationDirection;
+
+/// The result of a migration run (apply, revert or redo).
+#[derive(Debug, Default, Clone)]
+pub struct ApplyReport {
+    /// The migrations executed during the run, in execution order.
+    pub migrations: Vec<MigrationReport>,
+    /// Whether schema definitions have been (re)applied.
+    pub schemas_applied: bool,
+    /// Whether event definitions have been (re)applied.
+    pub events_applied: bool,
+    /// Whether the run was a dry run (every transaction has been cancelled).
+    pub dry_run: bool,
+}
+
+/// Details about a single migration executed during a run.
+#[derive(Debug, Clone)]
+pub struct MigrationReport {
+    /// Name of the migration, e.g. `20230101_120002_AddPost`.
+    pub name: String,
+    /// Whether the migration was applied or reverted.
+    pub direction: MigrationDirection,
+    /// Time spent executing the migration transaction.
+    pub duration: Duration,
+    /// The checksum stored in the `script_migration` table, if any.
+    pub checksum: Option<String>,
+    /// Number of statements of the migration file.
+    pub statements_count: usize,
+    /// Whether the migration was applied after a more recent migration (out-of-order mode).
+    pub out_of_order: bool,
+}
+
+impl Display for ApplyReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.schemas_applied {
+            writeln!(f, "Schema files successfully executed!")?;
+        }
+        if self.events_applied {
+            writeln!(f, "Event files successfully executed!")?;
+        }
+        if !self.migrations.is_empty() {
+            writeln!(f, "Migration files successfully executed!")?;
+        }
+
+        let out_of_order_migrations = self
+            .migrations
+            .iter()
+            .filter(|migration| migration.out_of_order)
+            .map(|migration| get_migration_display_name(&migration.name))
+            .collect::<Vec<_>>();
+        if !out_of_order_migrations.is_empty() {
+            writeln!(
+                f,
+                "Migration files applied out of order: {}",
+                out_of_order_migrations.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for MigrationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display_name = get_migration_display_name(&self.name);
+        let direction = match self.direction {
+            MigrationDirection::Forward => "applied",
+            MigrationDirection::Backward => "reverted",
+        };
+
+        write!(
+            f,
+            "{display_name} {direction} in {}ms ({} statements)",
+            self.duration.as_millis(),
+            self.statements_count
+        )?;
+
+        if self.out_of_order {
+            write!(f, " (out of order)")?;
+        }
+        if let Some(checksum) = &self.checksum {
+            write!(f, " [checksum: {checksum}]")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use color_eyre::eyre::{Error, eyre};
+use std::str::FromStr;
+
+/// The algorithm used to compute the checksum of a migration file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    /// SHA-256 of the content of the file, any change (even whitespace or comments) is a mismatch.
+    #[default]
+    Raw,
+    /// SHA-256 of the parsed statements, insensitive to formatting and comments.
+    Normalized,
+}
+
+impl ChecksumAlgorithm {
+    /// The name of the algorithm, as stored in the `checksum_algorithm` field of the history table.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Raw => "raw",
+            ChecksumAlgorithm::Normalized => "normalized",
+        }
+    }
+}
+
+impl FromStr for ChecksumAlgorithm {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "raw" => Ok(ChecksumAlgorithm::Raw),
+            "normalized" => Ok(ChecksumAlgorithm::Normalized),
+            _ => Err(eyre!("Unknown checksum algorithm '{value}'")),
+        }
+    }
+}
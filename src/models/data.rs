@@ -1,11 +1,20 @@
 use serde::{Deserialize, Serialize};
 use surrealdb::sql::Datetime;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ScriptMigration {
     pub script_name: String,
     pub executed_at: String,
     pub checksum: Option<String>,
+    pub checksum_algorithm: Option<String>,
+    pub executed_by: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub tool_version: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub run_id: Option<String>,
+    /// The schema definitions before the migration was applied,
+    /// for migrations without down migration (traditional approach only).
+    pub schema_snapshot: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,5 +1,6 @@
 use super::ApplyOperation;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MigrationDirection {
     Forward,
     Backward,
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use super::MigrationDirection;
+
+/// An action recorded in the migration event log.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MigrationEvent {
+    /// The action executed, e.g. `up`, `down`, `redo`, `baseline`, `fake`, `unfake` or `repair`.
+    pub action: String,
+    /// Name of the migration, e.g. `20230101_120002_AddPost`.
+    pub script_name: String,
+    /// `success` or `failure`.
+    pub outcome: String,
+    /// The error message, if the action failed.
+    pub error: Option<String>,
+    pub executed_at: String,
+    pub executed_by: Option<String>,
+    pub run_id: Option<String>,
+}
+
+/// The actions recorded in the migration event log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationEventAction {
+    Up,
+    Down,
+    Redo,
+    Baseline,
+    Fake,
+    Unfake,
+    Repair,
+}
+
+impl MigrationEventAction {
+    /// The name of the action, as stored in the `action` field of the event log.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationEventAction::Up => "up",
+            MigrationEventAction::Down => "down",
+            MigrationEventAction::Redo => "redo",
+            MigrationEventAction::Baseline => "baseline",
+            MigrationEventAction::Fake => "fake",
+            MigrationEventAction::Unfake => "unfake",
+            MigrationEventAction::Repair => "repair",
+        }
+    }
+}
+
+impl From<MigrationDirection> for MigrationEventAction {
+    fn from(direction: MigrationDirection) -> Self {
+        match direction {
+            MigrationDirection::Forward => MigrationEventAction::Up,
+            MigrationDirection::Backward => MigrationEventAction::Down,
+        }
+    }
+}
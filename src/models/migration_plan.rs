@@ -0,0 +1,76 @@
+use std::fmt::{Display, Formatter};
+use surrealdb::sql::Statement;
+
+use super::MigrationDirection;
+
+/// The ordered list of steps that would be executed by a migration run.
+#[derive(Debug, Default, Clone)]
+pub struct MigrationPlan {
+    /// The steps to execute, in execution order.
+    pub steps: Vec<MigrationPlanStep>,
+}
+
+/// A group of statements executed for a migration.
+#[derive(Debug, Clone)]
+pub struct MigrationPlanStep {
+    /// Name of the migration, `None` for the initial schema and event definitions,
+    /// and for the upgrade of the history table executed before the migrations.
+    pub migration: Option<String>,
+    /// Whether the migration is applied or reverted.
+    pub direction: MigrationDirection,
+    /// The kind of statements of this step.
+    pub kind: MigrationPlanStepKind,
+    /// The statements to execute.
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationPlanStepKind {
+    /// Schema definitions (re)applied with the migration.
+    Schemas,
+    /// Event definitions (re)applied with the migration.
+    Events,
+    /// Statements of the migration file (or down migration file).
+    Migration,
+    /// Migration written in Rust, see `CodeMigration`.
+    CodeMigration,
+    /// Statements generated to restore the previous schema and event definitions on revert.
+    Rollback,
+    /// Creation or deletion of the record in the `script_migration` table,
+    /// or the upgrade of the `script_migration` table.
+    ScriptMigration,
+}
+
+impl MigrationPlanStep {
+    /// The statements of this step, rendered as SurrealQL.
+    pub fn to_surql(&self) -> String {
+        self.statements
+            .iter()
+            .map(|statement| format!("{statement};"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Display for MigrationPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            let migration = match (step.migration.as_deref(), step.kind) {
+                (Some(migration), _) => migration,
+                (None, MigrationPlanStepKind::ScriptMigration) => "history table",
+                (None, _) => "initial definitions",
+            };
+            writeln!(
+                f,
+                "-- {:?} {:?} for {migration} --",
+                step.kind, step.direction
+            )?;
+
+            if !step.statements.is_empty() {
+                writeln!(f, "{}", step.to_surql())?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+/// The changes made to the migrations history to realign it with the migration files.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationRepair {
+    /// Names of the migrations whose stored checksum did not match the migration file.
+    pub checksum_mismatches: Vec<String>,
+    /// Names of the migrations applied without a checksum (before the `checksum` field existed).
+    pub missing_checksums: Vec<String>,
+    /// Names of the migrations applied whose file does not exist anymore.
+    /// Records without checksum are not included, as they can be code migrations.
+    pub missing_files: Vec<String>,
+}
+
+impl MigrationRepair {
+    /// Whether the migrations history is already aligned with the migration files.
+    pub fn is_empty(&self) -> bool {
+        self.checksum_mismatches.is_empty()
+            && self.missing_checksums.is_empty()
+            && self.missing_files.is_empty()
+    }
+}
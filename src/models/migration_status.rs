@@ -0,0 +1,25 @@
+use super::ScriptMigration;
+
+/// The state of the migrations of a database, compared to the migration files.
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    /// Number of migrations applied in the database.
+    pub applied: usize,
+    /// Names of the migrations not applied yet, in execution order.
+    pub pending: Vec<String>,
+    /// Names of the migrations applied in the database whose file does not exist anymore.
+    pub missing: Vec<String>,
+    /// Whether the `script_migration` table stores the checksum of the migrations.
+    pub supports_checksum: bool,
+    /// Whether the project uses migration definition files (schemas & events folders).
+    pub use_migration_definitions: bool,
+    /// The last migration applied in the database, if any.
+    pub last_applied: Option<ScriptMigration>,
+}
+
+impl MigrationStatus {
+    /// Whether every migration has been applied and no migration file is missing.
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty() && self.missing.is_empty()
+    }
+}
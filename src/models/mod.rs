@@ -1,7 +1,19 @@
 mod apply_operation;
+mod apply_report;
+mod checksum_algorithm;
 mod data;
 mod migration_direction;
+mod migration_event;
+mod migration_plan;
+mod migration_repair;
+mod migration_status;
 
 pub use apply_operation::*;
+pub use apply_report::*;
+pub use checksum_algorithm::*;
 pub use data::*;
 pub use migration_direction::*;
+pub use migration_event::*;
+pub use migration_plan::*;
+pub use migration_repair::*;
+pub use migration_status::*;
@@ -0,0 +1,79 @@
+use color_eyre::eyre::Report;
+use tracing::error;
+
+use crate::{
+    error::MigrationError,
+    models::{MigrationDirection, MigrationReport},
+};
+
+/// Hooks invoked during the lifecycle of a migration run (apply, revert or redo).
+///
+/// Every method has a default empty implementation, so you only need to implement the events you care about.
+///
+/// The progress of a migration run is also reported through `tracing` spans and events,
+/// an observer is only needed to react to those events in your own code.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use color_eyre::eyre::Result;
+/// use surrealdb_migrations::{MigrationDirection, MigrationObserver, MigrationReport, MigrationRunner};
+/// use surrealdb::engine::any::connect;
+///
+/// struct MetricsObserver;
+///
+/// impl MigrationObserver for MetricsObserver {
+///     fn after_migration(&self, name: &str, _direction: MigrationDirection, report: &MigrationReport) {
+///         println!("{name} took {}ms", report.duration.as_millis());
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let db = connect("ws://localhost:8000").await?;
+///
+/// MigrationRunner::new(&db)
+///     .with_observer(&MetricsObserver)
+///     .up()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait MigrationObserver: Send + Sync {
+    /// Called before a migration file is executed.
+    fn before_migration(&self, _name: &str, _direction: MigrationDirection) {}
+
+    /// Called after a migration file has been successfully executed.
+    fn after_migration(
+        &self,
+        _name: &str,
+        _direction: MigrationDirection,
+        _report: &MigrationReport,
+    ) {
+    }
+
+    /// Called when a migration file failed to be executed.
+    fn on_error(&self, _name: &str, _direction: MigrationDirection, _error: &MigrationError) {}
+
+    /// Called once schema and event definitions have been applied, when migrations are applied or reverted
+    /// (or when there is no migration to execute).
+    fn on_schema_applied(&self) {}
+}
+
+pub fn notify_error(
+    observer: Option<&dyn MigrationObserver>,
+    name: &str,
+    direction: MigrationDirection,
+    error: Report,
+) -> Report {
+    error!(migration = name, direction = ?direction, error = %error, "Migration failed");
+
+    match observer {
+        Some(observer) => {
+            let error = MigrationError::from(error);
+            observer.on_error(name, direction, &error);
+            error.into()
+        }
+        None => error,
+    }
+}
@@ -1,15 +1,24 @@
 use ::surrealdb::{Connection, Surreal};
-use color_eyre::eyre::{Result, eyre};
-use include_dir::Dir;
-use std::{collections::HashSet, path::Path};
+use color_eyre::eyre::Result;
+use std::{collections::HashSet, time::Instant};
+use tracing::{Instrument, debug, info, info_span};
 
 use crate::{
     apply::get_transaction_action,
+    code_migration::{NamedCodeMigration, extract_code_migrations_files, find_code_migration},
     common::get_migration_display_name,
     constants::ALL_TAGS,
+    error::MigrationError,
     io,
-    models::MigrationDirection,
-    surrealdb::{self},
+    lock::{self, LockOptions},
+    migration_event::{NewMigrationEvent, record_migration_events},
+    models::{
+        ApplyReport, ChecksumAlgorithm, MigrationDirection, MigrationEventAction, MigrationReport,
+    },
+    observer::{MigrationObserver, notify_error},
+    script_migration::{ChecksumSupport, RunMetadata, update_script_migration_checksum_statement},
+    source::MigrationSource,
+    surrealdb::{self, TransactionAction},
     validate_checksum::{self, ValidateChecksumArgs},
     validate_version_order::{self, ValidateVersionOrderArgs},
 };
@@ -17,33 +26,61 @@ use crate::{
 pub struct RedoArgs<'a, C: Connection> {
     pub migration_script: String,
     pub db: &'a Surreal<C>,
-    pub dir: Option<&'a Dir<'static>>,
-    pub display_logs: bool,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
+    pub code_migrations: &'a [NamedCodeMigration<'a, C>],
+    pub observer: Option<&'a dyn MigrationObserver>,
     pub dry_run: bool,
     pub validate_checksum: bool,
     pub validate_version_order: bool,
-    pub config_file: Option<&'a Path>,
     pub output: bool,
+    pub checksum_algorithm: ChecksumAlgorithm,
+    pub executor: Option<String>,
+    pub lock: Option<LockOptions>,
 }
 
-pub async fn main<C: Connection>(args: RedoArgs<'_, C>) -> Result<()> {
+pub async fn main<C: Connection>(args: RedoArgs<'_, C>) -> Result<ApplyReport> {
+    let span = info_span!(
+        "redo",
+        migration = %args.migration_script,
+        dry_run = args.dry_run,
+    );
+
+    let client = args.db;
+    let history_table = args.history_table;
+    let lock = match args.dry_run {
+        true => None,
+        false => args.lock,
+    };
+
+    lock::run_with_lock(client, history_table, lock, redo(args))
+        .instrument(span)
+        .await
+}
+
+async fn redo<C: Connection>(args: RedoArgs<'_, C>) -> Result<ApplyReport> {
     let RedoArgs {
         migration_script,
         db: client,
-        dir,
-        display_logs,
+        source,
+        history_table,
+        code_migrations,
+        observer,
         dry_run,
         validate_checksum,
         validate_version_order,
-        config_file,
         output,
+        checksum_algorithm,
+        executor,
+        lock: _,
     } = args;
 
     if validate_version_order {
         let validate_version_order_args = ValidateVersionOrderArgs {
             db: client,
-            dir,
-            config_file,
+            source,
+            history_table,
+            out_of_order: false,
         };
         validate_version_order::main(validate_version_order_args).await?;
     }
@@ -51,67 +88,199 @@ pub async fn main<C: Connection>(args: RedoArgs<'_, C>) -> Result<()> {
     if validate_checksum {
         let validate_checksum_args = ValidateChecksumArgs {
             db: client,
-            dir,
-            config_file,
+            source,
+            history_table,
         };
         validate_checksum::main(validate_checksum_args).await?;
     }
 
-    let display_logs = match dry_run {
-        true => false,
-        false => display_logs,
-    };
-
     let tags = HashSet::from([ALL_TAGS.into()]);
     let exclude_tags = HashSet::new();
 
-    let forward_migrations_files = io::extract_migrations_files(
-        config_file,
-        dir,
-        MigrationDirection::Forward,
-        &tags,
-        &exclude_tags,
-    );
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags)
+            .into_iter()
+            .chain(extract_code_migrations_files(
+                code_migrations,
+                MigrationDirection::Forward,
+                &tags,
+                &exclude_tags,
+            ))
+            .collect::<Vec<_>>();
 
     let migration_file = forward_migrations_files
         .into_iter()
         .find(|f| f.name == migration_script || f.full_name == migration_script);
 
     let Some(migration_file) = migration_file else {
-        return Err(eyre!("The migration file was not found"));
+        return Err(MigrationError::UnknownMigration {
+            migration: migration_script,
+        }
+        .into());
     };
 
     let migrations_applied =
-        surrealdb::list_script_migration_ordered_by_execution_date(client).await?;
+        surrealdb::list_script_migration_ordered_by_execution_date(client, history_table).await?;
 
-    let is_migration_already_applied = migrations_applied
-        .iter()
-        .any(|m| m.script_name == migration_file.name || m.script_name == migration_file.full_name);
-
-    if !is_migration_already_applied {
-        return Err(eyre!(
-            "This migration was not applied in the SurrealDB instance. Please make sure you correctly applied this migration before."
-        ));
-    }
+    let Some(migration_applied) = migrations_applied.iter().find(|m| {
+        m.script_name == migration_file.name || m.script_name == migration_file.full_name
+    }) else {
+        return Err(MigrationError::MigrationNotApplied {
+            migration: migration_file.name,
+        }
+        .into());
+    };
 
     let migration_content = migration_file.get_content().unwrap_or(String::new());
-    let statements = surrealdb::parse_statements(&migration_content)?;
+
+    let code_migration = find_code_migration(code_migrations, &migration_file.name);
+
+    // 💡 the stored checksum must match the file that was re-executed
+    let checksum = match code_migration {
+        Some(_) => None,
+        None => {
+            let script_migration_table_definition =
+                surrealdb::get_surrealdb_table_definition(client, history_table).await?;
+            ChecksumSupport::from_table_definition(&script_migration_table_definition)
+                .compute(&migration_content, checksum_algorithm)?
+        }
+    };
+    let update_checksum_statements = checksum
+        .iter()
+        .map(|checksum| {
+            update_script_migration_checksum_statement(
+                history_table,
+                &migration_applied.script_name,
+                checksum,
+            )
+        })
+        .collect::<Vec<_>>();
+    let checksum = checksum.map(|checksum| checksum.value);
+
+    let statements = surrealdb::parse_file_statements(&migration_file.name, &migration_content)
+        .map_err(|error| {
+            notify_error(
+                observer,
+                &migration_file.name,
+                MigrationDirection::Forward,
+                error,
+            )
+        })?;
 
     if output {
-        println!("{statements}");
+        info!(statements = %statements);
     }
 
-    if display_logs {
+    if !dry_run {
         let migration_display_name = get_migration_display_name(&migration_file.name);
-        println!("Executing migration {migration_display_name}...");
+        info!("Executing migration {migration_display_name}...");
     }
 
-    let transaction_action = get_transaction_action(dry_run);
-    surrealdb::apply_in_transaction(client, statements.0.0, transaction_action).await?;
+    if let Some(observer) = observer {
+        observer.before_migration(&migration_file.name, MigrationDirection::Forward);
+    }
+
+    let statements_count = statements.len();
+    let started_at = Instant::now();
+
+    let code_migration_up = match dry_run {
+        true => None,
+        false => code_migration.map(|code_migration| code_migration.up(client)),
+    };
+    let result = async {
+        if let Some(code_migration_up) = code_migration_up {
+            code_migration_up.await?;
+        }
+
+        match (migration_file.is_non_transactional(), dry_run) {
+            (true, true) => Ok(()),
+            (true, false) => {
+                surrealdb::apply_without_transaction(client, statements.0.0, &migration_file.name)
+                    .await?;
+                surrealdb::apply_in_transaction(
+                    client,
+                    update_checksum_statements,
+                    TransactionAction::Commit,
+                    Some(&migration_file.name),
+                )
+                .await
+            }
+            (false, _) => {
+                let statements = statements
+                    .0
+                    .0
+                    .into_iter()
+                    .chain(update_checksum_statements)
+                    .collect::<Vec<_>>();
 
-    if display_logs {
-        println!("Migration successfully re-executed!");
+                match statements.is_empty() {
+                    true => Ok(()),
+                    false => {
+                        let transaction_action = get_transaction_action(dry_run);
+                        surrealdb::apply_in_transaction(
+                            client,
+                            statements,
+                            transaction_action,
+                            Some(&migration_file.name),
+                        )
+                        .await
+                    }
+                }
+            }
+        }
     }
+    .await;
+
+    let events_recorded = match dry_run {
+        true => Ok(()),
+        false => {
+            let run_metadata = RunMetadata::new(executor, &tags);
+            let event = NewMigrationEvent::from_result(
+                MigrationEventAction::Redo,
+                &migration_file.name,
+                &result,
+            );
+            record_migration_events(client, history_table, &run_metadata, vec![event]).await
+        }
+    };
+
+    result.map_err(|error| {
+        notify_error(
+            observer,
+            &migration_file.name,
+            MigrationDirection::Forward,
+            error,
+        )
+    })?;
+    events_recorded?;
+
+    let migration_report = MigrationReport {
+        name: migration_file.name,
+        direction: MigrationDirection::Forward,
+        duration: started_at.elapsed(),
+        checksum,
+        statements_count,
+        out_of_order: false,
+    };
+
+    debug!(
+        duration_ms = migration_report.duration.as_millis() as u64,
+        statements_count, "Migration re-applied"
+    );
+
+    if let Some(observer) = observer {
+        observer.after_migration(
+            &migration_report.name,
+            MigrationDirection::Forward,
+            &migration_report,
+        );
+    }
+
+    let report = ApplyReport {
+        migrations: vec![migration_report],
+        dry_run,
+        ..Default::default()
+    };
 
-    Ok(())
+    Ok(report)
 }
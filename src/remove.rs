@@ -10,14 +10,15 @@ use crate::{
     file::SurqlFile,
     io::{self},
     models::MigrationDirection,
+    runbin::io::concat_path,
+    source::FileSystemSource,
 };
 
 pub fn main(config_file: Option<&Path>) -> Result<()> {
     let tags = HashSet::from([ALL_TAGS.into()]);
     let exclude_tags = HashSet::new();
     let forward_migrations_files = io::extract_migrations_files(
-        config_file,
-        None,
+        &FileSystemSource::from_config_file(config_file),
         MigrationDirection::Forward,
         &tags,
         &exclude_tags,
@@ -46,7 +47,7 @@ pub fn main(config_file: Option<&Path>) -> Result<()> {
 
 fn remove_migration_file(config_file: Option<&Path>, last_migration: &SurqlFile) -> Result<()> {
     let folder_path = config::retrieve_folder_path(config_file);
-    let migrations_path = io::concat_path(&folder_path, MIGRATIONS_DIR_NAME);
+    let migrations_path = concat_path(&folder_path, MIGRATIONS_DIR_NAME);
 
     let file_path = migrations_path.join(&last_migration.full_name);
 
@@ -60,7 +61,7 @@ fn remove_definition_file_if_exists(
     last_migration: &SurqlFile,
 ) -> Result<()> {
     let folder_path = config::retrieve_folder_path(config_file);
-    let migrations_path = io::concat_path(&folder_path, MIGRATIONS_DIR_NAME);
+    let migrations_path = concat_path(&folder_path, MIGRATIONS_DIR_NAME);
 
     let migration_definition_file_path = Path::new(&migrations_path)
         .join("definitions")
@@ -88,7 +89,7 @@ fn remove_nested_down_migration_file_if_exists(
     last_migration: &SurqlFile,
 ) -> Result<()> {
     let folder_path = config::retrieve_folder_path(config_file);
-    let migrations_path = io::concat_path(&folder_path, MIGRATIONS_DIR_NAME);
+    let migrations_path = concat_path(&folder_path, MIGRATIONS_DIR_NAME);
 
     let down_migration_file_path = Path::new(&migrations_path)
         .join(DOWN_MIGRATIONS_DIR_NAME)
@@ -106,7 +107,7 @@ fn remove_inlined_down_migration_file_if_exists(
     last_migration: &SurqlFile,
 ) -> Result<()> {
     let folder_path = config::retrieve_folder_path(config_file);
-    let migrations_path = io::concat_path(&folder_path, MIGRATIONS_DIR_NAME);
+    let migrations_path = concat_path(&folder_path, MIGRATIONS_DIR_NAME);
 
     let inlined_down_migration_file_path = Path::new(&migrations_path).join(format!(
         "{}{}",
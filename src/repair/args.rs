@@ -0,0 +1,36 @@
+use std::path::Path;
+
+use crate::{cli, input::SurrealdbConfiguration};
+
+pub struct RepairArgs<'a> {
+    pub db_configuration: SurrealdbConfiguration,
+    pub yes: bool,
+    pub config_file: Option<&'a Path>,
+}
+
+impl<'a> RepairArgs<'a> {
+    pub fn from(value: cli::RepairArgs, config_file: Option<&'a Path>) -> Self {
+        let cli::RepairArgs {
+            address,
+            ns,
+            db,
+            username,
+            password,
+            yes,
+        } = value;
+
+        let db_configuration = SurrealdbConfiguration {
+            address,
+            ns,
+            db,
+            username,
+            password,
+        };
+
+        RepairArgs {
+            db_configuration,
+            yes,
+            config_file,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+pub mod args;
+
+pub use args::RepairArgs;
+use color_eyre::eyre::Result;
+use std::io::{self, Write};
+
+use crate::{
+    config,
+    lock::LockOptions,
+    migration_repair::{self, MigrationRepairArgs},
+    runbin::surrealdb::create_surrealdb_client,
+    source::FileSystemSource,
+};
+
+pub async fn main(args: RepairArgs<'_>) -> Result<()> {
+    let RepairArgs {
+        db_configuration,
+        yes,
+        config_file,
+    } = args;
+
+    let client = create_surrealdb_client(config_file, &db_configuration).await?;
+
+    let source = FileSystemSource::from_config_file(config_file);
+    let history_table = config::retrieve_history_table(config_file);
+    let checksum_algorithm = config::retrieve_checksum_algorithm(config_file)?;
+    let executor = config::retrieve_executor(config_file);
+
+    let repair = migration_repair::main(MigrationRepairArgs {
+        db: &client,
+        source: &source,
+        history_table: &history_table,
+        code_migrations: &[],
+        checksum_algorithm,
+        dry_run: true,
+        executor: executor.clone(),
+        lock: None,
+    })
+    .await?;
+
+    if repair.is_empty() {
+        println!("✅ The migrations history is aligned with the migration files.");
+        return Ok(());
+    }
+
+    print_changes("Checksums to update:", &repair.checksum_mismatches);
+    print_changes("Checksums to add:", &repair.missing_checksums);
+    print_changes(
+        "Missing files to remove from history:",
+        &repair.missing_files,
+    );
+
+    if !yes && !ask_confirmation()? {
+        println!("Repair cancelled.");
+        return Ok(());
+    }
+
+    migration_repair::main(MigrationRepairArgs {
+        db: &client,
+        source: &source,
+        history_table: &history_table,
+        code_migrations: &[],
+        checksum_algorithm,
+        dry_run: false,
+        executor,
+        lock: Some(LockOptions::default()),
+    })
+    .await?;
+
+    println!("Migrations history successfully repaired!");
+
+    Ok(())
+}
+
+fn print_changes(title: &str, migrations: &[String]) {
+    if migrations.is_empty() {
+        return;
+    }
+
+    println!("{title}");
+    for migration in migrations {
+        println!("- {migration}");
+    }
+    println!();
+}
+
+fn ask_confirmation() -> Result<bool> {
+    print!("Do you want to repair the migrations history? [y/N] ");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    let confirmed = matches!(answer.trim().to_lowercase().as_str(), "y" | "yes");
+    Ok(confirmed)
+}
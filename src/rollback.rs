@@ -0,0 +1,324 @@
+use ::surrealdb::sql::{
+    Statement,
+    statements::{
+        DefineStatement, RemoveAccessStatement, RemoveAnalyzerStatement, RemoveDatabaseStatement,
+        RemoveEventStatement, RemoveFieldStatement, RemoveFunctionStatement, RemoveIndexStatement,
+        RemoveModelStatement, RemoveNamespaceStatement, RemoveParamStatement, RemoveStatement,
+        RemoveTableStatement, RemoveUserStatement,
+    },
+};
+use tracing::warn;
+
+/// Identifies a definition, regardless of its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionKey {
+    pub kind: &'static str,
+    /// The table for table-scoped definitions, the base for users and accesses.
+    pub scope: Option<String>,
+    pub name: String,
+}
+
+/// Compute the statements that restore the `previous` definitions once the `next` definitions are applied:
+/// definitions added are removed, definitions changed or removed are (re)defined as they were before.
+pub fn get_rollback_statements(
+    next_statements: Vec<Statement>,
+    previous_statements: Vec<Statement>,
+) -> Vec<Statement> {
+    let next_definitions = extract_define_statements(next_statements);
+    let previous_definitions = extract_define_statements(previous_statements);
+
+    let added_definitions = next_definitions
+        .iter()
+        .filter(|(next_key, _)| {
+            previous_definitions
+                .iter()
+                .all(|(previous_key, _)| previous_key != next_key)
+        })
+        .collect::<Vec<_>>();
+
+    let tables_to_remove = added_definitions
+        .iter()
+        .filter(|(key, _)| key.kind == "table")
+        .map(|(key, _)| key.name.clone())
+        .collect::<Vec<_>>();
+
+    let remove_statements = added_definitions
+        .into_iter()
+        .rev()
+        .filter(|(key, _)| match (&key.scope, key.kind) {
+            (Some(table_name), "field" | "index" | "event") => {
+                !tables_to_remove.contains(table_name)
+            }
+            _ => true,
+        })
+        .filter_map(|(_, definition)| {
+            let remove_statement = get_remove_statement(definition);
+            if remove_statement.is_none() {
+                warn!(statement = %definition, "Cannot generate a rollback for this definition");
+            }
+            remove_statement
+        })
+        .map(Statement::Remove);
+
+    let restore_statements = previous_definitions
+        .iter()
+        .filter(|(previous_key, previous_definition)| {
+            let next_definition = next_definitions
+                .iter()
+                .find(|(next_key, _)| next_key == previous_key);
+
+            match next_definition {
+                Some((_, next_definition)) => {
+                    normalize(next_definition).to_string()
+                        != normalize(previous_definition).to_string()
+                }
+                None => true,
+            }
+        })
+        .map(|(_, previous_definition)| {
+            let mut definition = normalize(previous_definition);
+            set_overwrite(&mut definition);
+            Statement::Define(definition)
+        });
+
+    remove_statements.chain(restore_statements).collect()
+}
+
+/// Generate the REMOVE statement of a definition, if SurrealDB supports it.
+pub fn get_remove_statement(definition: &DefineStatement) -> Option<RemoveStatement> {
+    match definition {
+        DefineStatement::Namespace(definition) => {
+            let mut s = RemoveNamespaceStatement::default();
+            s.name = definition.name.clone();
+            Some(RemoveStatement::Namespace(s))
+        }
+        DefineStatement::Database(definition) => {
+            let mut s = RemoveDatabaseStatement::default();
+            s.name = definition.name.clone();
+            Some(RemoveStatement::Database(s))
+        }
+        DefineStatement::Function(definition) => {
+            let mut s = RemoveFunctionStatement::default();
+            s.name = definition.name.clone();
+            Some(RemoveStatement::Function(s))
+        }
+        DefineStatement::Analyzer(definition) => {
+            let mut s = RemoveAnalyzerStatement::default();
+            s.name = definition.name.clone();
+            Some(RemoveStatement::Analyzer(s))
+        }
+        DefineStatement::Param(definition) => {
+            let mut s = RemoveParamStatement::default();
+            s.name = definition.name.clone();
+            Some(RemoveStatement::Param(s))
+        }
+        DefineStatement::Table(definition) => {
+            let mut s = RemoveTableStatement::default();
+            s.name = definition.name.clone();
+            Some(RemoveStatement::Table(s))
+        }
+        DefineStatement::Event(definition) => {
+            let mut s = RemoveEventStatement::default();
+            s.name = definition.name.clone();
+            s.what = definition.what.clone();
+            Some(RemoveStatement::Event(s))
+        }
+        DefineStatement::Field(definition) => {
+            let mut s = RemoveFieldStatement::default();
+            s.name = definition.name.clone();
+            s.what = definition.what.clone();
+            Some(RemoveStatement::Field(s))
+        }
+        DefineStatement::Index(definition) => {
+            let mut s = RemoveIndexStatement::default();
+            s.name = definition.name.clone();
+            s.what = definition.what.clone();
+            Some(RemoveStatement::Index(s))
+        }
+        DefineStatement::User(definition) => {
+            let mut s = RemoveUserStatement::default();
+            s.name = definition.name.clone();
+            s.base = definition.base.clone();
+            Some(RemoveStatement::User(s))
+        }
+        DefineStatement::Model(definition) => {
+            let mut s = RemoveModelStatement::default();
+            s.name = definition.name.clone();
+            s.version = definition.version.clone();
+            Some(RemoveStatement::Model(s))
+        }
+        DefineStatement::Access(definition) => {
+            let mut s = RemoveAccessStatement::default();
+            s.name = definition.name.clone();
+            s.base = definition.base.clone();
+            Some(RemoveStatement::Access(s))
+        }
+        // 💡 there is no REMOVE statement for other definitions (e.g. config, api)
+        _ => None,
+    }
+}
+
+fn extract_define_statements(statements: Vec<Statement>) -> Vec<(DefinitionKey, DefineStatement)> {
+    statements
+        .into_iter()
+        .filter_map(|statement| match statement {
+            Statement::Define(define_statement) => Some(define_statement),
+            _ => None,
+        })
+        .map(|define_statement| (get_definition_key(&define_statement), define_statement))
+        .collect()
+}
+
+pub fn get_definition_key(definition: &DefineStatement) -> DefinitionKey {
+    let (kind, scope, name) = match definition {
+        DefineStatement::Namespace(s) => ("namespace", None, s.name.to_string()),
+        DefineStatement::Database(s) => ("database", None, s.name.to_string()),
+        DefineStatement::Function(s) => ("function", None, s.name.to_string()),
+        DefineStatement::Analyzer(s) => ("analyzer", None, s.name.to_string()),
+        DefineStatement::Param(s) => ("param", None, s.name.to_string()),
+        DefineStatement::Table(s) => ("table", None, s.name.to_string()),
+        DefineStatement::Event(s) => ("event", Some(s.what.to_string()), s.name.to_string()),
+        DefineStatement::Field(s) => ("field", Some(s.what.to_string()), s.name.to_string()),
+        DefineStatement::Index(s) => ("index", Some(s.what.to_string()), s.name.to_string()),
+        DefineStatement::User(s) => ("user", Some(s.base.to_string()), s.name.to_string()),
+        DefineStatement::Model(s) => ("model", Some(s.version.clone()), s.name.to_string()),
+        DefineStatement::Access(s) => ("access", Some(s.base.to_string()), s.name.to_string()),
+        DefineStatement::Config(s) => ("config", None, s.inner.name()),
+        DefineStatement::Api(s) => ("api", None, s.path.to_string()),
+        _ => ("unknown", None, definition.to_string()),
+    };
+
+    DefinitionKey { kind, scope, name }
+}
+
+/// Remove the clauses that do not change the definition itself (`OVERWRITE`, `IF NOT EXISTS`).
+pub fn normalize(definition: &DefineStatement) -> DefineStatement {
+    let mut definition = definition.clone();
+    set_flags(&mut definition, false, false);
+    definition
+}
+
+pub fn set_overwrite(definition: &mut DefineStatement) {
+    set_flags(definition, true, false);
+}
+
+fn set_flags(definition: &mut DefineStatement, overwrite: bool, if_not_exists: bool) {
+    match definition {
+        DefineStatement::Namespace(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Database(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Function(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Analyzer(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Param(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Table(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Event(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Field(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Index(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::User(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Model(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Access(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Config(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        DefineStatement::Api(s) => {
+            s.overwrite = overwrite;
+            s.if_not_exists = if_not_exists;
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surrealdb::parse_statements;
+
+    fn get_rollback(next: &str, previous: &str) -> Vec<String> {
+        let next_statements = parse_statements(next).unwrap().0.0;
+        let previous_statements = parse_statements(previous).unwrap().0.0;
+
+        get_rollback_statements(next_statements, previous_statements)
+            .iter()
+            .map(|statement| statement.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn remove_added_table_with_its_fields() {
+        let rollback = get_rollback(
+            "DEFINE TABLE post SCHEMAFULL; DEFINE FIELD title ON post TYPE string;",
+            "",
+        );
+
+        assert_eq!(rollback, vec!["REMOVE TABLE post"]);
+    }
+
+    #[test]
+    fn restore_changed_and_removed_definitions() {
+        let rollback = get_rollback(
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE option<string>;
+DEFINE INDEX title_index ON post FIELDS title;",
+            "DEFINE TABLE post SCHEMAFULL;
+DEFINE FIELD title ON post TYPE string;
+DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello ' + $name; };
+DEFINE PARAM $limit VALUE 10;",
+        );
+
+        assert_eq!(
+            rollback,
+            vec![
+                "REMOVE INDEX title_index ON post",
+                "DEFINE FIELD OVERWRITE title ON post TYPE string PERMISSIONS FULL",
+                "DEFINE FUNCTION OVERWRITE fn::greet($name: string) { RETURN 'Hello ' + $name; } PERMISSIONS FULL",
+                "DEFINE PARAM OVERWRITE $limit VALUE 10 PERMISSIONS FULL",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignore_overwrite_clause_when_comparing_definitions() {
+        let rollback = get_rollback(
+            "DEFINE ANALYZER OVERWRITE simple TOKENIZERS blank;",
+            "DEFINE ANALYZER simple TOKENIZERS blank;",
+        );
+
+        assert!(rollback.is_empty());
+    }
+}
@@ -1,27 +1,37 @@
-use include_dir::Dir;
-use std::{collections::HashSet, path::Path};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
-use crate::io::{concat_files_content, extract_events_files, extract_schemas_files};
+use crate::{
+    io::{concat_files_content, extract_events_files, extract_schemas_files},
+    source::MigrationSource,
+};
+
+pub fn concat_path(folder_path: &Option<String>, dir_name: &str) -> PathBuf {
+    match folder_path.to_owned() {
+        Some(folder_path) => Path::new(&folder_path).join(dir_name),
+        None => Path::new(dir_name).to_path_buf(),
+    }
+}
 
 pub fn extract_schema_definitions(
-    config_file: Option<&Path>,
-    embedded_dir: Option<&Dir<'static>>,
+    source: &dyn MigrationSource,
     tags: &HashSet<String>,
     exclude_tags: &HashSet<String>,
 ) -> String {
-    let schemas_files = extract_schemas_files(config_file, embedded_dir, tags, exclude_tags)
+    let schemas_files = extract_schemas_files(source, tags, exclude_tags)
         .ok()
         .unwrap_or_default();
     concat_files_content(&schemas_files)
 }
 
 pub fn extract_event_definitions(
-    config_file: Option<&Path>,
-    embedded_dir: Option<&Dir<'static>>,
+    source: &dyn MigrationSource,
     tags: &HashSet<String>,
     exclude_tags: &HashSet<String>,
 ) -> String {
-    let events_files = extract_events_files(config_file, embedded_dir, tags, exclude_tags)
+    let events_files = extract_events_files(source, tags, exclude_tags)
         .ok()
         .unwrap_or_default();
     concat_files_content(&events_files)
@@ -0,0 +1,86 @@
+use std::fmt::Debug;
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+const LIBRARY_TARGET: &str = "surrealdb_migrations";
+
+/// Displays the events of the migration runs in the console, as human readable lines:
+/// `info` events are written to stdout, `warn` and `error` events to stderr.
+pub struct ConsoleLayer;
+
+impl<S: Subscriber> Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+
+        if !metadata.target().starts_with(LIBRARY_TARGET) {
+            return;
+        }
+
+        let mut visitor = ConsoleVisitor::default();
+        event.record(&mut visitor);
+
+        match *metadata.level() {
+            Level::INFO => {
+                if let Some(message) = visitor.message {
+                    println!("{message}");
+                }
+                if let Some(statements) = visitor.statements {
+                    println!("{statements}");
+                }
+            }
+            Level::WARN => eprintln!("Warning: {}", visitor.to_problem_line()),
+            Level::ERROR => eprintln!("Error: {}", visitor.to_problem_line()),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct ConsoleVisitor {
+    message: Option<String>,
+    statements: Option<String>,
+    migration: Option<String>,
+    statement: Option<String>,
+    error: Option<String>,
+}
+
+impl ConsoleVisitor {
+    /// The message of a warning or an error, followed by its context (migration, statement, error).
+    fn to_problem_line(&self) -> String {
+        let message = self.message.as_deref().unwrap_or_default();
+
+        let context = [&self.migration, &self.statement, &self.error]
+            .into_iter()
+            .flatten()
+            .map(|value| value.as_str())
+            .collect::<Vec<_>>();
+
+        match context.is_empty() {
+            true => message.to_string(),
+            false => format!("{message} ({})", context.join(", ")),
+        }
+    }
+}
+
+impl Visit for ConsoleVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "migration" => self.migration = Some(value.to_string()),
+            _ => self.record_debug(field, &value),
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        match field.name() {
+            "message" => self.message = Some(format!("{value:?}")),
+            "statements" => self.statements = Some(format!("{value:?}")),
+            "migration" => self.migration = Some(format!("{value:?}")),
+            "statement" => self.statement = Some(format!("{value:?}")),
+            "error" => self.error = Some(format!("{value:?}")),
+            _ => {}
+        }
+    }
+}
@@ -5,4 +5,5 @@ pub mod config;
 pub mod db_config;
 mod env;
 pub mod io;
+pub mod logs;
 pub mod surrealdb;
@@ -19,15 +19,16 @@ use crate::{
         ALL_TAGS, DOWN_MIGRATIONS_DIR_NAME, EVENTS_DIR_NAME,
         INITIAL_TRADITIONAL_MIGRATION_FILENAME, MIGRATIONS_DIR_NAME, SCHEMAS_DIR_NAME,
     },
-    io::{self, ensures_folder_exists},
-    runbin::io::{extract_event_definitions, extract_schema_definitions},
+    io::ensures_folder_exists,
+    runbin::io::{concat_path, extract_event_definitions, extract_schema_definitions},
+    source::FileSystemSource,
     surrealdb::parse_statements,
 };
 
 pub fn apply_before_scaffold(folder_path: Option<String>) -> Result<()> {
-    let schemas_dir_path = io::concat_path(&folder_path, SCHEMAS_DIR_NAME);
-    let events_dir_path = io::concat_path(&folder_path, EVENTS_DIR_NAME);
-    let migrations_dir_path = io::concat_path(&folder_path, MIGRATIONS_DIR_NAME);
+    let schemas_dir_path = concat_path(&folder_path, SCHEMAS_DIR_NAME);
+    let events_dir_path = concat_path(&folder_path, EVENTS_DIR_NAME);
+    let migrations_dir_path = concat_path(&folder_path, MIGRATIONS_DIR_NAME);
 
     fails_if_folder_already_exists(&schemas_dir_path, SCHEMAS_DIR_NAME)?;
     fails_if_folder_already_exists(&events_dir_path, EVENTS_DIR_NAME)?;
@@ -41,9 +42,9 @@ pub fn apply_after_scaffold(
     traditional: bool,
     folder_path: Option<String>,
 ) -> Result<()> {
-    let schemas_dir_path = io::concat_path(&folder_path, SCHEMAS_DIR_NAME);
-    let events_dir_path = io::concat_path(&folder_path, EVENTS_DIR_NAME);
-    let migrations_dir_path = io::concat_path(&folder_path, MIGRATIONS_DIR_NAME);
+    let schemas_dir_path = concat_path(&folder_path, SCHEMAS_DIR_NAME);
+    let events_dir_path = concat_path(&folder_path, EVENTS_DIR_NAME);
+    let migrations_dir_path = concat_path(&folder_path, MIGRATIONS_DIR_NAME);
 
     ensures_folder_exists(&schemas_dir_path)?;
     ensures_folder_exists(&events_dir_path)?;
@@ -58,10 +59,11 @@ pub fn apply_after_scaffold(
         let tags = HashSet::from([ALL_TAGS.into()]);
         let exclude_tags = HashSet::new();
 
+        let source = FileSystemSource::from_config_file(config_file);
+
         // extract surql files
-        let schema_definitions =
-            extract_schema_definitions(config_file, None, &tags, &exclude_tags);
-        let event_definitions = extract_event_definitions(config_file, None, &tags, &exclude_tags);
+        let schema_definitions = extract_schema_definitions(&source, &tags, &exclude_tags);
+        let event_definitions = extract_event_definitions(&source, &tags, &exclude_tags);
 
         // concat surql statements
         let schemas_statements = parse_statements(&schema_definitions)?;
@@ -9,7 +9,7 @@ use crate::{
     cli::{ScaffoldSchemaDbType, ScaffoldTemplate},
     config,
     constants::{SCHEMAS_DIR_NAME, SCRIPT_MIGRATION_TABLE_NAME, SURQL_FILE_EXTENSION},
-    io,
+    runbin::io::concat_path,
 };
 
 use super::common::{
@@ -104,7 +104,7 @@ fn scaffold_from_schema(
 
     copy_template_files_to_current_dir(ScaffoldTemplate::Empty, folder_path.to_owned())?;
 
-    let schemas_dir_path = io::concat_path(&folder_path, SCHEMAS_DIR_NAME);
+    let schemas_dir_path = concat_path(&folder_path, SCHEMAS_DIR_NAME);
 
     for (table_name, line_definitions) in schema.tables {
         let filename = format!("{}{}", table_name, SURQL_FILE_EXTENSION);
@@ -0,0 +1,103 @@
+use ::surrealdb::{
+    Connection, Surreal,
+    sql::{Statement, statements::DefineStatement},
+};
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use std::collections::HashMap;
+use tracing::warn;
+
+use crate::{
+    constants::MIGRATION_LOCK_TABLE_NAME,
+    migration_event::get_event_table_name,
+    rollback,
+    surrealdb::{self, get_surrealdb_database_definition, get_surrealdb_table_definition},
+};
+
+/// Take a snapshot of the schema of the database, as a list of DEFINE statements:
+/// accesses, analyzers, functions, params and tables, with their fields, indexes and events.
+/// The tables used by the migration tool itself are excluded.
+pub async fn take_schema_snapshot<C: Connection>(
+    client: &Surreal<C>,
+    history_table: &str,
+) -> Result<String> {
+    let excluded_tables = [
+        history_table.to_string(),
+        get_event_table_name(history_table),
+        MIGRATION_LOCK_TABLE_NAME.to_string(),
+    ];
+
+    let database_definition = get_surrealdb_database_definition(client).await?;
+
+    let tables = database_definition
+        .tables
+        .into_iter()
+        .filter(|(table_name, _)| !excluded_tables.contains(table_name))
+        .collect::<HashMap<_, _>>();
+
+    let mut definitions = get_sorted_definitions(database_definition.accesses);
+    definitions.extend(get_sorted_definitions(database_definition.analyzers));
+    definitions.extend(get_sorted_definitions(database_definition.functions));
+    definitions.extend(get_sorted_definitions(database_definition.params));
+
+    for table_name in tables.keys().sorted() {
+        let table_definition = get_surrealdb_table_definition(client, table_name).await?;
+
+        definitions.push(tables[table_name].to_string());
+        definitions.extend(get_sorted_definitions(table_definition.fields));
+        definitions.extend(get_sorted_definitions(table_definition.indexes));
+        definitions.extend(get_sorted_definitions(table_definition.events));
+    }
+
+    let schema_snapshot = definitions
+        .into_iter()
+        .map(|definition| format!("{definition};"))
+        .join("\n");
+
+    Ok(schema_snapshot)
+}
+
+/// Compute the statements that restore the schema of a snapshot,
+/// from the schema of another snapshot (e.g. the current schema).
+pub fn get_snapshot_rollback_statements(
+    current_snapshot: &str,
+    previous_snapshot: &str,
+) -> Result<Vec<Statement>> {
+    let current_statements = parse_snapshot(current_snapshot)?;
+    let previous_statements = parse_snapshot(previous_snapshot)?;
+
+    let statements = rollback::get_rollback_statements(current_statements, previous_statements)
+        .into_iter()
+        .filter(|statement| match statement {
+            // 💡 the secrets of access methods are redacted by the server, so they cannot be restored
+            Statement::Define(DefineStatement::Access(access)) => {
+                warn!(
+                    "Cannot restore the access definition '{}', its secrets are not part of the schema snapshot",
+                    access.name
+                );
+                false
+            }
+            _ => true,
+        })
+        .collect();
+
+    Ok(statements)
+}
+
+fn get_sorted_definitions(definitions: HashMap<String, String>) -> Vec<String> {
+    definitions
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, definition)| definition)
+        .collect()
+}
+
+fn parse_snapshot(snapshot: &str) -> Result<Vec<Statement>> {
+    match snapshot.is_empty() {
+        true => Ok(vec![]),
+        false => {
+            let statements = surrealdb::parse_statements(snapshot)?;
+            Ok(statements.0.0)
+        }
+    }
+}
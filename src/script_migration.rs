@@ -0,0 +1,558 @@
+use ::surrealdb::{
+    Connection, Surreal,
+    sql::{
+        Array, Cond, Data, Expression, Function, Idiom, Kind, Operator, Output, Statement, Uuid,
+        Value, Values,
+        statements::{
+            CreateStatement, DefineFieldStatement, DefineStatement, DeleteStatement, SetStatement,
+            UpdateStatement,
+        },
+    },
+};
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use lexicmp::natural_lexical_cmp;
+use sha2::{Digest, Sha256};
+use std::{collections::HashSet, env, time::Duration};
+
+use crate::{
+    file::SurqlFile,
+    io,
+    models::{ChecksumAlgorithm, ScriptMigration},
+    source::MigrationSource,
+    surrealdb::{self, SurrealdbTableDefinition},
+};
+
+const MIGRATION_STARTED_AT_PARAM: &str = "migration_started_at";
+
+/// Statements to execute before recording migrations without executing them (baseline, fake).
+pub struct HistoryTableSetup {
+    /// The definition of the history table, when the table does not exist yet,
+    /// followed by the fields added to the history table over time.
+    pub statements: Vec<Statement>,
+    pub checksum_support: ChecksumSupport,
+}
+
+/// The checksum fields defined in the history table.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChecksumSupport {
+    pub checksum: bool,
+    pub algorithm: bool,
+}
+
+impl ChecksumSupport {
+    pub fn from_table_definition(table_definition: &SurrealdbTableDefinition) -> Self {
+        ChecksumSupport {
+            checksum: table_definition.fields.contains_key("checksum"),
+            algorithm: table_definition.fields.contains_key("checksum_algorithm"),
+        }
+    }
+
+    pub fn from_statements(statements: &[Statement], history_table: &str) -> Self {
+        let is_field_defined = |field_name: &str| {
+            statements
+                .iter()
+                .any(|statement| is_define_field_statement(statement, history_table, field_name))
+        };
+
+        ChecksumSupport {
+            checksum: is_field_defined("checksum"),
+            algorithm: is_field_defined("checksum_algorithm"),
+        }
+    }
+
+    pub fn or(self, other: ChecksumSupport) -> Self {
+        ChecksumSupport {
+            checksum: self.checksum || other.checksum,
+            algorithm: self.algorithm || other.algorithm,
+        }
+    }
+
+    /// Computes the checksum to store in the history table, if any.
+    /// The raw algorithm is used when the history table cannot store the algorithm,
+    /// so that the checksum is validated with the right algorithm afterwards.
+    pub fn compute(
+        &self,
+        content: &str,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<Option<ScriptChecksum>> {
+        if !self.checksum {
+            return Ok(None);
+        }
+
+        let checksum = match self.algorithm {
+            true => ScriptChecksum {
+                value: compute_checksum(content, algorithm)?,
+                algorithm: Some(algorithm),
+            },
+            false => ScriptChecksum {
+                value: compute_checksum(content, ChecksumAlgorithm::Raw)?,
+                algorithm: None,
+            },
+        };
+
+        Ok(Some(checksum))
+    }
+}
+
+/// A checksum stored in the history table.
+pub struct ScriptChecksum {
+    pub value: String,
+    /// `None` if the history table does not store the checksum algorithm.
+    pub algorithm: Option<ChecksumAlgorithm>,
+}
+
+pub fn compute_checksum(content: &str, algorithm: ChecksumAlgorithm) -> Result<String> {
+    let checksum = match algorithm {
+        ChecksumAlgorithm::Raw => Sha256::digest(content).to_vec(),
+        ChecksumAlgorithm::Normalized => {
+            let statements = surrealdb::parse_statements(content)?;
+            Sha256::digest(statements.to_string()).to_vec()
+        }
+    };
+
+    Ok(hex::encode(checksum))
+}
+
+/// Computes the checksum of a migration with the algorithm stored in the history table,
+/// migrations applied before the algorithm was stored use the raw algorithm.
+pub fn compute_checksum_for_script_migration(
+    content: &str,
+    checksum_algorithm: Option<&str>,
+) -> Result<String> {
+    let algorithm = match checksum_algorithm {
+        Some(checksum_algorithm) => checksum_algorithm.parse()?,
+        None => ChecksumAlgorithm::Raw,
+    };
+
+    compute_checksum(content, algorithm)
+}
+
+/// The most recent migration applied, by name.
+/// It differs from the last migration executed when migrations have been applied out of order.
+pub fn get_latest_migration_applied(
+    migrations_applied: &[ScriptMigration],
+) -> Option<&ScriptMigration> {
+    migrations_applied
+        .iter()
+        .max_by(|a, b| natural_lexical_cmp(&a.script_name, &b.script_name))
+}
+
+/// Information about a run, stored alongside each migration it records in the history table.
+pub struct RunMetadata {
+    pub run_id: String,
+    pub executed_by: Option<String>,
+    pub tool_version: String,
+    pub tags: Vec<String>,
+}
+
+impl RunMetadata {
+    /// Uses the configured executor if any, or the user running the process.
+    pub fn new(executor: Option<String>, tags: &HashSet<String>) -> Self {
+        let tags = tags.iter().cloned().sorted().collect();
+
+        RunMetadata {
+            run_id: Uuid::new_v7().to_raw(),
+            executed_by: executor.or_else(get_os_user),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            tags,
+        }
+    }
+}
+
+fn get_os_user() -> Option<String> {
+    env::var("USER").or_else(|_| env::var("USERNAME")).ok()
+}
+
+/// The fields added to the history table after its first definition, with their type.
+fn get_history_table_upgrade_fields() -> Vec<(&'static str, Kind)> {
+    vec![
+        ("executed_by", Kind::String),
+        ("duration_ms", Kind::Int),
+        ("tool_version", Kind::String),
+        ("tags", Kind::Array(Box::new(Kind::String), None)),
+        ("run_id", Kind::String),
+        ("schema_snapshot", Kind::String),
+    ]
+}
+
+/// Defines the fields missing in a history table created with an older version of the tool,
+/// so that the run metadata can be stored in SCHEMAFULL tables.
+pub fn upgrade_history_table_statements(history_table: &str) -> Vec<Statement> {
+    get_history_table_upgrade_fields()
+        .into_iter()
+        .map(|(field_name, kind)| {
+            let mut define_field_statement = DefineFieldStatement::default();
+            define_field_statement.name = Idiom::from(field_name);
+            define_field_statement.what = history_table.into();
+            define_field_statement.kind = Some(Kind::Option(Box::new(kind)));
+            define_field_statement.if_not_exists = true;
+
+            Statement::Define(DefineStatement::Field(define_field_statement))
+        })
+        .collect()
+}
+
+pub fn create_script_migration_statement(
+    history_table: &str,
+    script_name: &str,
+    checksum: Option<&ScriptChecksum>,
+    run_metadata: &RunMetadata,
+    schema_snapshot: Option<&str>,
+) -> Statement {
+    let mut what = Values::default();
+    what.0.push(Value::Table(history_table.into()));
+
+    let mut set_script_expressions = vec![(
+        Idiom::from("script_name"),
+        Operator::Equal,
+        Value::Strand(script_name.into()),
+    )];
+    if let Some(checksum) = checksum {
+        set_script_expressions.extend(get_set_checksum_expressions(checksum));
+    }
+    set_script_expressions.extend(get_set_run_metadata_expressions(run_metadata));
+    if let Some(schema_snapshot) = schema_snapshot {
+        set_script_expressions.push((
+            Idiom::from("schema_snapshot"),
+            Operator::Equal,
+            Value::Strand(schema_snapshot.into()),
+        ));
+    }
+
+    let mut create_migration_script_statement = CreateStatement::default();
+    create_migration_script_statement.what = what;
+    create_migration_script_statement.data = Some(Data::SetExpression(set_script_expressions));
+    create_migration_script_statement.output = Some(Output::None);
+
+    Statement::Create(create_migration_script_statement)
+}
+
+pub fn update_script_migration_checksum_statement(
+    history_table: &str,
+    script_name: &str,
+    checksum: &ScriptChecksum,
+) -> Statement {
+    let mut what = Values::default();
+    what.0.push(Value::Table(history_table.into()));
+
+    let mut update_migration_script_statement = UpdateStatement::default();
+    update_migration_script_statement.what = what;
+    update_migration_script_statement.data =
+        Some(Data::SetExpression(get_set_checksum_expressions(checksum)));
+    update_migration_script_statement.cond = Some(script_name_cond(script_name));
+    update_migration_script_statement.output = Some(Output::None);
+
+    Statement::Update(update_migration_script_statement)
+}
+
+fn get_set_checksum_expressions(checksum: &ScriptChecksum) -> Vec<(Idiom, Operator, Value)> {
+    let mut expressions = vec![(
+        Idiom::from("checksum"),
+        Operator::Equal,
+        Value::Strand(checksum.value.as_str().into()),
+    )];
+    if let Some(algorithm) = checksum.algorithm {
+        expressions.push((
+            Idiom::from("checksum_algorithm"),
+            Operator::Equal,
+            Value::Strand(algorithm.as_str().into()),
+        ));
+    }
+
+    expressions
+}
+
+fn get_set_run_metadata_expressions(run_metadata: &RunMetadata) -> Vec<(Idiom, Operator, Value)> {
+    let mut expressions = vec![
+        (
+            Idiom::from("run_id"),
+            Operator::Equal,
+            Value::Strand(run_metadata.run_id.as_str().into()),
+        ),
+        (
+            Idiom::from("tool_version"),
+            Operator::Equal,
+            Value::Strand(run_metadata.tool_version.as_str().into()),
+        ),
+        (
+            Idiom::from("tags"),
+            Operator::Equal,
+            Value::Array(Array::from(run_metadata.tags.clone())),
+        ),
+    ];
+    if let Some(executed_by) = &run_metadata.executed_by {
+        expressions.push((
+            Idiom::from("executed_by"),
+            Operator::Equal,
+            Value::Strand(executed_by.as_str().into()),
+        ));
+    }
+
+    expressions
+}
+
+/// Remembers when a migration started, as `$migration_started_at`, inside the transaction
+/// that records it. The time already spent (e.g. by a code migration) is subtracted.
+pub fn set_migration_started_at_statement(elapsed: Duration) -> Statement {
+    let mut set_statement = SetStatement::default();
+    set_statement.name = MIGRATION_STARTED_AT_PARAM.to_string();
+    set_statement.what = Value::Expression(Box::new(Expression::Binary {
+        l: time_now(),
+        o: Operator::Sub,
+        r: Value::Duration(elapsed.into()),
+    }));
+
+    Statement::Set(set_statement)
+}
+
+/// Stores the duration of the migrations of a run, from the `$migration_started_at` variable.
+/// It is executed in the same transaction as the creation of the migrations records.
+pub fn update_script_migration_duration_statement(
+    history_table: &str,
+    run_id: &str,
+    script_names: &[&str],
+) -> Statement {
+    let mut what = Values::default();
+    what.0.push(Value::Table(history_table.into()));
+
+    let mut cond = Cond::default();
+    cond.0 = Value::Expression(Box::new(Expression::Binary {
+        l: Value::Expression(Box::new(Expression::Binary {
+            l: Value::Idiom("run_id".into()),
+            o: Operator::Exact,
+            r: Value::Strand(run_id.into()),
+        })),
+        o: Operator::And,
+        r: Value::Expression(Box::new(Expression::Binary {
+            l: Value::Idiom("script_name".into()),
+            o: Operator::Inside,
+            r: Value::Array(Array::from(
+                script_names
+                    .iter()
+                    .map(|script_name| script_name.to_string())
+                    .collect::<Vec<_>>(),
+            )),
+        })),
+    }));
+
+    let elapsed = Value::Expression(Box::new(Expression::Binary {
+        l: time_now(),
+        o: Operator::Sub,
+        r: Value::Param(MIGRATION_STARTED_AT_PARAM.into()),
+    }));
+
+    let mut update_migration_script_statement = UpdateStatement::default();
+    update_migration_script_statement.what = what;
+    update_migration_script_statement.data = Some(Data::SetExpression(vec![(
+        Idiom::from("duration_ms"),
+        Operator::Equal,
+        Value::Function(Box::new(Function::Normal(
+            "duration::millis".to_string(),
+            vec![elapsed],
+        ))),
+    )]));
+    update_migration_script_statement.cond = Some(cond);
+    update_migration_script_statement.output = Some(Output::None);
+
+    Statement::Update(update_migration_script_statement)
+}
+
+fn time_now() -> Value {
+    Value::Function(Box::new(Function::Normal("time::now".to_string(), vec![])))
+}
+
+pub fn delete_script_migration_statement(history_table: &str, script_name: &str) -> Statement {
+    let mut what = Values::default();
+    what.0.push(Value::Table(history_table.into()));
+
+    let mut delete_migration_script_statement = DeleteStatement::default();
+    delete_migration_script_statement.what = what;
+    delete_migration_script_statement.cond = Some(script_name_cond(script_name));
+    delete_migration_script_statement.output = Some(Output::None);
+
+    Statement::Delete(delete_migration_script_statement)
+}
+
+fn script_name_cond(script_name: &str) -> Cond {
+    let mut cond = Cond::default();
+    cond.0 = Value::Expression(Box::new(Expression::Binary {
+        l: Value::Idiom("script_name".into()),
+        o: Operator::Exact,
+        r: Value::Strand(script_name.into()),
+    }));
+
+    cond
+}
+
+pub async fn prepare_history_table<C: Connection>(
+    client: &Surreal<C>,
+    source: &dyn MigrationSource,
+    history_table: &str,
+    migrations_files: &[&SurqlFile],
+    tags: &HashSet<String>,
+    exclude_tags: &HashSet<String>,
+) -> Result<HistoryTableSetup> {
+    let is_history_table_defined =
+        surrealdb::get_surrealdb_table_exists(client, history_table).await?;
+
+    if is_history_table_defined {
+        let history_table_definition =
+            surrealdb::get_surrealdb_table_definition(client, history_table).await?;
+
+        return Ok(HistoryTableSetup {
+            statements: upgrade_history_table_statements(history_table),
+            checksum_support: ChecksumSupport::from_table_definition(&history_table_definition),
+        });
+    }
+
+    // the history table is defined from the schema files (or the initial migration file),
+    // so that the records created match the table definition
+    let schemas_files = io::extract_schemas_files(source, tags, exclude_tags)?;
+
+    let files_content = schemas_files
+        .iter()
+        .chain(migrations_files.iter().copied())
+        .map(|f| f.get_content().unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    let mut statements = Vec::new();
+
+    for content in files_content {
+        let file_statements = surrealdb::parse_statements(&content)?;
+        statements.extend(
+            file_statements
+                .into_iter()
+                .filter(|statement| is_history_table_definition(statement, history_table)),
+        );
+    }
+
+    let checksum_support = ChecksumSupport::from_statements(&statements, history_table);
+
+    statements.extend(upgrade_history_table_statements(history_table));
+
+    Ok(HistoryTableSetup {
+        statements,
+        checksum_support,
+    })
+}
+
+fn is_history_table_definition(statement: &Statement, history_table: &str) -> bool {
+    match statement {
+        Statement::Define(DefineStatement::Table(define_table_statement)) => {
+            define_table_statement.name.0 == history_table
+        }
+        Statement::Define(DefineStatement::Field(define_field_statement)) => {
+            define_field_statement.what.0 == history_table
+        }
+        _ => false,
+    }
+}
+
+fn is_define_field_statement(statement: &Statement, history_table: &str, field_name: &str) -> bool {
+    match statement {
+        Statement::Define(DefineStatement::Field(define_field_statement)) => {
+            define_field_statement.name.to_string() == field_name
+                && define_field_statement.what.0 == history_table
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrade_history_table_defines_missing_fields() {
+        let statements = upgrade_history_table_statements("script_migration")
+            .into_iter()
+            .map(|statement| statement.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            statements,
+            vec![
+                "DEFINE FIELD IF NOT EXISTS executed_by ON script_migration TYPE option<string> PERMISSIONS FULL",
+                "DEFINE FIELD IF NOT EXISTS duration_ms ON script_migration TYPE option<int> PERMISSIONS FULL",
+                "DEFINE FIELD IF NOT EXISTS tool_version ON script_migration TYPE option<string> PERMISSIONS FULL",
+                "DEFINE FIELD IF NOT EXISTS tags ON script_migration TYPE option<array<string>> PERMISSIONS FULL",
+                "DEFINE FIELD IF NOT EXISTS run_id ON script_migration TYPE option<string> PERMISSIONS FULL",
+                "DEFINE FIELD IF NOT EXISTS schema_snapshot ON script_migration TYPE option<string> PERMISSIONS FULL",
+            ]
+        );
+    }
+
+    #[test]
+    fn duration_is_computed_from_migration_start() {
+        let statements = [
+            set_migration_started_at_statement(Duration::from_millis(1500)),
+            update_script_migration_duration_statement(
+                "script_migration",
+                "run",
+                &["20240101_120000_AddPost"],
+            ),
+        ]
+        .map(|statement| statement.to_string());
+
+        assert_eq!(
+            statements,
+            [
+                "LET $migration_started_at = time::now() - 1s500ms",
+                "UPDATE script_migration SET duration_ms = duration::millis(time::now() - $migration_started_at) WHERE run_id == 'run' AND script_name INSIDE ['20240101_120000_AddPost'] RETURN NONE",
+            ]
+        );
+    }
+
+    #[test]
+    fn normalized_checksum_ignores_formatting_and_comments() -> Result<()> {
+        let content = "CREATE post SET title = 'Hello';";
+        let reformatted_content =
+            "-- Create the first post\nCREATE post\n    SET title = 'Hello';\n";
+
+        let checksum = compute_checksum(content, ChecksumAlgorithm::Normalized)?;
+        let reformatted_checksum =
+            compute_checksum(reformatted_content, ChecksumAlgorithm::Normalized)?;
+
+        assert_eq!(checksum, reformatted_checksum);
+
+        Ok(())
+    }
+
+    #[test]
+    fn raw_checksum_detects_formatting_changes() -> Result<()> {
+        let content = "CREATE post SET title = 'Hello';";
+        let reformatted_content = "CREATE post\n    SET title = 'Hello';\n";
+
+        let checksum = compute_checksum(content, ChecksumAlgorithm::Raw)?;
+        let reformatted_checksum = compute_checksum(reformatted_content, ChecksumAlgorithm::Raw)?;
+
+        assert_ne!(checksum, reformatted_checksum);
+
+        Ok(())
+    }
+
+    #[test]
+    fn normalized_checksum_detects_statement_changes() -> Result<()> {
+        let content = "CREATE post SET title = 'Hello';";
+        let changed_content = "CREATE post SET title = 'Hello world';";
+
+        let checksum = compute_checksum(content, ChecksumAlgorithm::Normalized)?;
+        let changed_checksum = compute_checksum(changed_content, ChecksumAlgorithm::Normalized)?;
+
+        assert_ne!(checksum, changed_checksum);
+
+        Ok(())
+    }
+
+    #[test]
+    fn script_migration_without_algorithm_uses_raw_checksum() -> Result<()> {
+        let content = "CREATE post SET title = 'Hello';";
+
+        let checksum = compute_checksum_for_script_migration(content, None)?;
+
+        assert_eq!(checksum, compute_checksum(content, ChecksumAlgorithm::Raw)?);
+
+        Ok(())
+    }
+}
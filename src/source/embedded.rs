@@ -0,0 +1,62 @@
+use color_eyre::eyre::Result;
+use include_dir::Dir;
+use std::path::{Path, PathBuf};
+
+use crate::source::MigrationSource;
+
+/// Read migration files embedded in the binary with the `include_dir` crate.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// use include_dir::{include_dir, Dir};
+/// use surrealdb_migrations::EmbeddedSource;
+///
+/// const DB_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/blog");
+///
+/// let source = EmbeddedSource::new(&DB_DIR);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedSource<'a> {
+    dir: &'a Dir<'static>,
+}
+
+impl<'a> EmbeddedSource<'a> {
+    /// Create a source reading files from an embedded directory.
+    pub fn new(dir: &'a Dir<'static>) -> Self {
+        EmbeddedSource { dir }
+    }
+}
+
+impl MigrationSource for EmbeddedSource<'_> {
+    fn list_files(&self, folder: &Path) -> Result<Vec<PathBuf>> {
+        let files = match self.dir.get_dir(folder) {
+            Some(dir) => {
+                let mut files = Vec::new();
+                nested_list_files(dir, &mut files);
+                files
+            }
+            None => vec![],
+        };
+
+        Ok(files)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Option<String>> {
+        let content = self
+            .dir
+            .get_file(path)
+            .and_then(|file| file.contents_utf8())
+            .map(|content| content.to_string());
+
+        Ok(content)
+    }
+}
+
+fn nested_list_files(dir: &Dir<'static>, files: &mut Vec<PathBuf>) {
+    files.extend(dir.files().map(|file| file.path().to_path_buf()));
+
+    for dir in dir.dirs() {
+        nested_list_files(dir, files);
+    }
+}
@@ -0,0 +1,83 @@
+use color_eyre::eyre::{Result, WrapErr};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::MigrationSource;
+use crate::config;
+
+/// Read migration files from a folder of the filesystem.
+#[derive(Debug, Clone)]
+pub struct FileSystemSource {
+    root: PathBuf,
+}
+
+impl FileSystemSource {
+    /// Create a source reading files from the `root` folder.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileSystemSource { root: root.into() }
+    }
+
+    /// Create a source reading files from the folder set in the configuration file (`[core] path`),
+    /// or from the current directory by default.
+    pub fn from_config_file(config_file: Option<&Path>) -> Self {
+        let folder_path = config::retrieve_folder_path(config_file);
+        FileSystemSource::new(folder_path.unwrap_or_default())
+    }
+
+    /// The folder files are read from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl MigrationSource for FileSystemSource {
+    fn list_files(&self, folder: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        nested_list_files(&self.root, folder, &mut files)
+            .wrap_err(format!("Error listing {} directory", folder.display()))?;
+
+        files.sort();
+
+        Ok(files)
+    }
+
+    fn filesystem_root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Option<String>> {
+        let file_path = self.root.join(path);
+
+        if !file_path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .wrap_err(format!("Error reading file {}", file_path.display()))?;
+
+        Ok(Some(content))
+    }
+}
+
+fn nested_list_files(root: &Path, folder: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let dir_path = root.join(folder);
+
+    if !dir_path.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir_path)? {
+        let entry = entry?;
+        let path = folder.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            nested_list_files(root, &path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use color_eyre::eyre::Result;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::source::MigrationSource;
+
+/// Migration files stored in memory, useful for generated migrations or tests.
+///
+/// ## Examples
+///
+/// ```rust
+/// use surrealdb_migrations::InMemorySource;
+///
+/// let source = InMemorySource::new()
+///     .with_file(
+///         "schemas/script_migration.surql",
+///         "DEFINE TABLE script_migration SCHEMAFULL;",
+///     )
+///     .with_file(
+///         "migrations/20231225_120000_AddAdminUser.surql",
+///         "CREATE user:admin SET name = 'Admin';",
+///     );
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct InMemorySource {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl InMemorySource {
+    /// Create an empty source.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a file to the source, replacing any existing file at the same path.
+    ///
+    /// ## Arguments
+    ///
+    /// * `path` - Path of the file, relative to the root of the source (e.g. `schemas/post.surql`)
+    /// * `content` - Content of the file
+    pub fn with_file(mut self, path: impl Into<PathBuf>, content: impl Into<String>) -> Self {
+        self.files.insert(path.into(), content.into());
+        self
+    }
+}
+
+impl MigrationSource for InMemorySource {
+    fn list_files(&self, folder: &Path) -> Result<Vec<PathBuf>> {
+        let files = self
+            .files
+            .keys()
+            .filter(|path| path.starts_with(folder))
+            .cloned()
+            .collect();
+
+        Ok(files)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Option<String>> {
+        Ok(self.files.get(path).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_files_in_nested_folders() {
+        let source = InMemorySource::new()
+            .with_file("schemas/post.surql", "")
+            .with_file("migrations/20231225_AddPost.surql", "")
+            .with_file("migrations/down/20231225_AddPost.surql", "")
+            .with_file("migrations_old/20231225_AddPost.surql", "");
+
+        let files = source.list_migrations().unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("migrations/20231225_AddPost.surql"),
+                PathBuf::from("migrations/down/20231225_AddPost.surql"),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_missing_file() {
+        let source = InMemorySource::new().with_file("schemas/post.surql", "");
+
+        let content = source.read_file(Path::new("schemas/user.surql")).unwrap();
+
+        assert_eq!(content, None);
+    }
+}
@@ -0,0 +1,56 @@
+mod filesystem;
+mod planned;
+
+use color_eyre::eyre::Result;
+use std::path::{Path, PathBuf};
+
+use crate::constants::{
+    DEFINITIONS_DIR_NAME, EVENTS_DIR_NAME, MIGRATIONS_DIR_NAME, SCHEMAS_DIR_NAME,
+};
+
+pub use self::{filesystem::FileSystemSource, planned::PlannedSource};
+
+/// Where the schemas, events, migrations and definitions files are read from.
+///
+/// Paths are always relative to the root of the source, i.e. the folder containing
+/// the `schemas`, `events` and `migrations` folders.
+///
+/// Built-in implementations are [`FileSystemSource`] (the default), [`EmbeddedSource`](crate::EmbeddedSource)
+/// for files embedded with `include_dir` and [`InMemorySource`](crate::InMemorySource).
+/// Implement this trait to load migrations from anywhere else (`rust-embed`, a blob store, generated code, etc...).
+pub trait MigrationSource: Send + Sync {
+    /// List the paths of all files inside a folder, including files in nested folders.
+    ///
+    /// Should return an empty list if the folder does not exist.
+    fn list_files(&self, folder: &Path) -> Result<Vec<PathBuf>>;
+
+    /// Read the content of a file, or `None` if the file does not exist.
+    fn read_file(&self, path: &Path) -> Result<Option<String>>;
+
+    /// The folder of the filesystem containing the files, if any.
+    ///
+    /// When set, the definition files are generated inside this folder when applying migrations.
+    fn filesystem_root(&self) -> Option<&Path> {
+        None
+    }
+
+    /// List the files of the `schemas` folder.
+    fn list_schemas(&self) -> Result<Vec<PathBuf>> {
+        self.list_files(Path::new(SCHEMAS_DIR_NAME))
+    }
+
+    /// List the files of the `events` folder.
+    fn list_events(&self) -> Result<Vec<PathBuf>> {
+        self.list_files(Path::new(EVENTS_DIR_NAME))
+    }
+
+    /// List the files of the `migrations` folder.
+    fn list_migrations(&self) -> Result<Vec<PathBuf>> {
+        self.list_files(Path::new(MIGRATIONS_DIR_NAME))
+    }
+
+    /// List the files of the `migrations/definitions` folder.
+    fn list_definitions(&self) -> Result<Vec<PathBuf>> {
+        self.list_files(Path::new(DEFINITIONS_DIR_NAME))
+    }
+}
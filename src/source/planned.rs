@@ -0,0 +1,105 @@
+use color_eyre::eyre::Result;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use super::MigrationSource;
+use crate::io::DefinitionFileChange;
+
+/// A source seen as if the pending changes of the definition files were written,
+/// used to plan migrations without touching the filesystem.
+pub struct PlannedSource<'a> {
+    source: &'a dyn MigrationSource,
+    changes: BTreeMap<PathBuf, Option<String>>,
+}
+
+impl<'a> PlannedSource<'a> {
+    pub fn new(source: &'a dyn MigrationSource, changes: Vec<DefinitionFileChange>) -> Self {
+        let changes = changes
+            .into_iter()
+            .map(|change| (change.path, change.content))
+            .collect();
+
+        PlannedSource { source, changes }
+    }
+}
+
+impl MigrationSource for PlannedSource<'_> {
+    fn list_files(&self, folder: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = self
+            .source
+            .list_files(folder)?
+            .into_iter()
+            .filter(|path| !self.changes.contains_key(path))
+            .collect::<Vec<_>>();
+
+        let written_files = self
+            .changes
+            .iter()
+            .filter(|(path, content)| path.starts_with(folder) && content.is_some())
+            .map(|(path, _)| path.to_path_buf());
+        files.extend(written_files);
+
+        files.sort();
+
+        Ok(files)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Option<String>> {
+        match self.changes.get(path) {
+            Some(content) => Ok(content.clone()),
+            None => self.source.read_file(path),
+        }
+    }
+
+    fn filesystem_root(&self) -> Option<&Path> {
+        self.source.filesystem_root()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_source::InMemorySource;
+
+    #[test]
+    fn overlay_definition_files_changes() {
+        let source = InMemorySource::new()
+            .with_file("migrations/definitions/_initial.json", "initial")
+            .with_file("migrations/definitions/20231225_AddPost.json", "post");
+
+        let changes = vec![
+            DefinitionFileChange {
+                path: PathBuf::from("migrations/definitions/20231225_AddPost.json"),
+                content: None,
+            },
+            DefinitionFileChange {
+                path: PathBuf::from("migrations/definitions/20231226_AddComment.json"),
+                content: Some("comment".to_string()),
+            },
+        ];
+
+        let source = PlannedSource::new(&source, changes);
+
+        assert_eq!(
+            source.list_definitions().unwrap(),
+            vec![
+                PathBuf::from("migrations/definitions/20231226_AddComment.json"),
+                PathBuf::from("migrations/definitions/_initial.json"),
+            ]
+        );
+        assert_eq!(
+            source
+                .read_file(Path::new("migrations/definitions/20231225_AddPost.json"))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            source
+                .read_file(Path::new("migrations/definitions/_initial.json"))
+                .unwrap(),
+            Some("initial".to_string())
+        );
+    }
+}
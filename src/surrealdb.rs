@@ -4,7 +4,7 @@ use serde::Deserialize;
 use std::collections::HashMap;
 use surrealdb::{Connection, Surreal};
 
-use crate::{constants::SCRIPT_MIGRATION_TABLE_NAME, models::ScriptMigration};
+use crate::{error::MigrationError, models::ScriptMigration};
 
 pub async fn get_surrealdb_table_exists<C: Connection>(
     client: &Surreal<C>,
@@ -31,9 +31,42 @@ pub async fn get_surrealdb_table_definitions<C: Connection>(
     Ok(table_definitions)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SurrealdbDatabaseDefinition {
+    #[serde(default)]
+    pub accesses: HashMap<String, String>,
+    #[serde(default)]
+    pub analyzers: HashMap<String, String>,
+    #[serde(default)]
+    pub functions: HashMap<String, String>,
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+    #[serde(default)]
+    pub tables: HashMap<String, String>,
+}
+
+pub async fn get_surrealdb_database_definition<C: Connection>(
+    client: &Surreal<C>,
+) -> Result<SurrealdbDatabaseDefinition> {
+    let response = client
+        .query(surrealdb::sql::statements::InfoStatement::Db(false, None))
+        .await?;
+
+    let mut response = response.check()?;
+
+    let result: Option<SurrealdbDatabaseDefinition> = response.take(0)?;
+    let database_definition = result.context("Failed to get database definition")?;
+
+    Ok(database_definition)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SurrealdbTableDefinition {
     pub fields: HashMap<String, String>,
+    #[serde(default)]
+    pub indexes: HashMap<String, String>,
+    #[serde(default)]
+    pub events: HashMap<String, String>,
 }
 
 pub async fn get_surrealdb_table_definition<C: Connection>(
@@ -60,9 +93,10 @@ pub async fn get_surrealdb_table_definition<C: Connection>(
 
 pub async fn list_script_migration_ordered_by_execution_date<C: Connection>(
     client: &Surreal<C>,
+    history_table: &str,
 ) -> Result<Vec<ScriptMigration>> {
-    if get_surrealdb_table_exists(client, SCRIPT_MIGRATION_TABLE_NAME).await? {
-        let mut result = list_script_migration(client).await?;
+    if get_surrealdb_table_exists(client, history_table).await? {
+        let mut result = list_script_migration(client, history_table).await?;
         result.sort_by_key(|m| m.executed_at.clone());
         Ok(result)
     } else {
@@ -70,8 +104,11 @@ pub async fn list_script_migration_ordered_by_execution_date<C: Connection>(
     }
 }
 
-async fn list_script_migration<C: Connection>(client: &Surreal<C>) -> Result<Vec<ScriptMigration>> {
-    let result = client.select(SCRIPT_MIGRATION_TABLE_NAME).await?;
+async fn list_script_migration<C: Connection>(
+    client: &Surreal<C>,
+    history_table: &str,
+) -> Result<Vec<ScriptMigration>> {
+    let result = client.select(history_table).await?;
     Ok(result)
 }
 
@@ -85,22 +122,31 @@ pub fn parse_statements(query_str: &str) -> Result<surrealdb::sql::Query> {
     Ok(query)
 }
 
-pub fn is_define_checksum_statement(statement: &surrealdb::sql::Statement) -> bool {
-    match statement {
-        surrealdb::sql::Statement::Define(surrealdb::sql::statements::DefineStatement::Field(
-            define_field_statement,
-        )) => {
-            define_field_statement.name.to_string() == "checksum"
-                && define_field_statement.what.0 == SCRIPT_MIGRATION_TABLE_NAME
+pub fn parse_file_statements(file_name: &str, query_str: &str) -> Result<surrealdb::sql::Query> {
+    parse_statements(query_str).map_err(|error| {
+        let message = error.to_string();
+
+        MigrationError::ParseError {
+            file: file_name.to_string(),
+            line: extract_error_line(&message),
+            message,
         }
-        _ => false,
-    }
+        .into()
+    })
+}
+
+fn extract_error_line(message: &str) -> Option<usize> {
+    let (_, location) = message.split_once("--> [")?;
+    let (line, _) = location.split_once(':')?;
+
+    line.parse().ok()
 }
 
 pub async fn apply_in_transaction<C: Connection>(
     client: &Surreal<C>,
     statements: Vec<surrealdb::sql::Statement>,
     action: TransactionAction,
+    migration_name: Option<&str>,
 ) -> Result<()> {
     let mut statements = statements.clone();
 
@@ -165,7 +211,11 @@ pub async fn apply_in_transaction<C: Connection>(
                     .chain(error_messages.into_iter())
                     .collect_vec();
 
-                return Err(eyre!(error_messages.join("\n")));
+                return Err(MigrationError::TransactionFailed {
+                    migration: migration_name.map(|name| name.to_string()),
+                    errors: error_messages,
+                }
+                .into());
             }
 
             Ok(())
@@ -173,8 +223,63 @@ pub async fn apply_in_transaction<C: Connection>(
     }
 }
 
+pub async fn apply_without_transaction<C: Connection>(
+    client: &Surreal<C>,
+    statements: Vec<surrealdb::sql::Statement>,
+    migration_name: &str,
+) -> Result<()> {
+    let mut response = client.query(statements).await?;
+
+    let errors = response.take_errors();
+    if !errors.is_empty() {
+        let error_messages = errors
+            .into_iter()
+            .sorted_by_key(|(index, _)| *index)
+            .map(|(_, e)| e.to_string())
+            .collect_vec();
+
+        return Err(MigrationError::NonTransactionalMigrationFailed {
+            migration: migration_name.to_string(),
+            errors: error_messages,
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, PartialEq)]
 pub enum TransactionAction {
     Commit,
     Rollback,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_valid_file_statements() {
+        let result = parse_file_statements("file", "DEFINE TABLE post SCHEMALESS;");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn parse_invalid_file_statements_with_line() {
+        let result = parse_file_statements(
+            "20230101_120001_AddPost",
+            "DEFINE TABLE post SCHEMALESS;\nDEFINE TABLE comment SCHEMANONE;",
+        );
+
+        let error = result.unwrap_err().downcast::<MigrationError>().unwrap();
+
+        match error {
+            MigrationError::ParseError { file, line, .. } => {
+                assert_eq!(file, "20230101_120001_AddPost");
+                assert_eq!(line, Some(2));
+            }
+            _ => panic!("Expected a parse error"),
+        }
+    }
+}
@@ -1,42 +1,38 @@
 use ::surrealdb::{Connection, Surreal};
-use color_eyre::eyre::{eyre, Result};
-use include_dir::Dir;
-use sha2::{Digest, Sha256};
-use std::{collections::HashSet, path::Path};
+use color_eyre::eyre::Result;
+use std::collections::HashSet;
 
 use crate::{
     constants::ALL_TAGS,
+    error::MigrationError,
     io::{self},
     models::MigrationDirection,
+    script_migration::compute_checksum_for_script_migration,
+    source::MigrationSource,
     surrealdb,
 };
 
 pub struct ValidateChecksumArgs<'a, C: Connection> {
     pub db: &'a Surreal<C>,
-    pub dir: Option<&'a Dir<'static>>,
-    pub config_file: Option<&'a Path>,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
 }
 
 pub async fn main<C: Connection>(args: ValidateChecksumArgs<'_, C>) -> Result<()> {
     let ValidateChecksumArgs {
         db: client,
-        dir,
-        config_file,
+        source,
+        history_table,
     } = args;
 
     let migrations_applied =
-        surrealdb::list_script_migration_ordered_by_execution_date(client).await?;
+        surrealdb::list_script_migration_ordered_by_execution_date(client, history_table).await?;
 
     let tags = HashSet::from([ALL_TAGS.into()]);
     let exclude_tags = HashSet::new();
 
-    let forward_migrations_files = io::extract_migrations_files(
-        config_file,
-        dir,
-        MigrationDirection::Forward,
-        &tags,
-        &exclude_tags,
-    );
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags);
 
     for migration_applied in migrations_applied {
         if let Some(checksum) = migration_applied.checksum {
@@ -45,21 +41,22 @@ pub async fn main<C: Connection>(args: ValidateChecksumArgs<'_, C>) -> Result<()
                 .find(|f| f.name == migration_applied.script_name);
 
             if let Some(migration_file) = migration_file {
-                let file_checksum =
-                    Sha256::digest(migration_file.get_content().unwrap_or_default()).to_vec();
-                let file_checksum = hex::encode(file_checksum);
+                let file_checksum = compute_checksum_for_script_migration(
+                    &migration_file.get_content().unwrap_or_default(),
+                    migration_applied.checksum_algorithm.as_deref(),
+                )?;
 
                 if checksum != file_checksum {
-                    return Err(eyre!(
-                        "The checksum does not match for migration '{}'.",
-                        migration_applied.script_name
-                    ));
+                    return Err(MigrationError::ChecksumMismatch {
+                        migration: migration_applied.script_name,
+                    }
+                    .into());
                 }
             } else {
-                return Err(eyre!(
-                    "The migration file '{}' does not exist.",
-                    migration_applied.script_name
-                ));
+                return Err(MigrationError::MissingMigrationFile {
+                    migration: migration_applied.script_name,
+                }
+                .into());
             }
         }
     }
@@ -1,43 +1,44 @@
 use ::surrealdb::{Connection, Surreal};
-use color_eyre::eyre::{eyre, Result};
-use include_dir::Dir;
+use color_eyre::eyre::Result;
 use lexicmp::natural_lexical_cmp;
-use std::{cmp::Ordering, collections::HashSet, path::Path};
+use std::{cmp::Ordering, collections::HashSet};
+use tracing::info;
 
 use crate::{
     constants::ALL_TAGS,
+    error::MigrationError,
     file::SurqlFile,
     io::{self},
     models::{MigrationDirection, ScriptMigration},
+    script_migration::get_latest_migration_applied,
+    source::MigrationSource,
     surrealdb,
 };
 
 pub struct ValidateVersionOrderArgs<'a, C: Connection> {
     pub db: &'a Surreal<C>,
-    pub dir: Option<&'a Dir<'static>>,
-    pub config_file: Option<&'a Path>,
+    pub source: &'a dyn MigrationSource,
+    pub history_table: &'a str,
+    /// Accept the migrations older than the last migration applied, they are reported instead.
+    pub out_of_order: bool,
 }
 
 pub async fn main<C: Connection>(args: ValidateVersionOrderArgs<'_, C>) -> Result<()> {
     let ValidateVersionOrderArgs {
         db: client,
-        dir,
-        config_file,
+        source,
+        history_table,
+        out_of_order,
     } = args;
 
     let migrations_applied =
-        surrealdb::list_script_migration_ordered_by_execution_date(client).await?;
+        surrealdb::list_script_migration_ordered_by_execution_date(client, history_table).await?;
 
     let tags = HashSet::from([ALL_TAGS.into()]);
     let exclude_tags = HashSet::new();
 
-    let forward_migrations_files = io::extract_migrations_files(
-        config_file,
-        dir,
-        MigrationDirection::Forward,
-        &tags,
-        &exclude_tags,
-    );
+    let forward_migrations_files =
+        io::extract_migrations_files(source, MigrationDirection::Forward, &tags, &exclude_tags);
 
     let migrations_not_applied = forward_migrations_files
         .into_iter()
@@ -46,7 +47,7 @@ pub async fn main<C: Connection>(args: ValidateVersionOrderArgs<'_, C>) -> Resul
         })
         .collect::<Vec<_>>();
 
-    let last_migration_applied = migrations_applied.last();
+    let last_migration_applied = get_latest_migration_applied(&migrations_applied);
 
     let migrations_not_applied_before_last_applied =
         if let Some(last_migration_applied) = last_migration_applied {
@@ -61,18 +62,27 @@ pub async fn main<C: Connection>(args: ValidateVersionOrderArgs<'_, C>) -> Resul
             Vec::new()
         };
 
-    if !migrations_not_applied_before_last_applied.is_empty() {
-        let migration_names = migrations_not_applied_before_last_applied
-            .iter()
-            .map(|migration_file| migration_file.name.to_string())
-            .collect::<Vec<_>>();
-
-        Err(eyre!(
-            "The following migrations have not been applied: {}",
-            migration_names.join(", ")
-        ))
-    } else {
-        Ok(())
+    if migrations_not_applied_before_last_applied.is_empty() {
+        return Ok(());
+    }
+
+    let migration_names = migrations_not_applied_before_last_applied
+        .iter()
+        .map(|migration_file| migration_file.name.to_string())
+        .collect::<Vec<_>>();
+
+    match out_of_order {
+        true => {
+            info!(
+                migrations = migration_names.join(", "),
+                "Migrations older than the last migration applied will be applied out of order"
+            );
+            Ok(())
+        }
+        false => Err(MigrationError::VersionOrderGap {
+            migrations: migration_names,
+        }
+        .into()),
     }
 }
 
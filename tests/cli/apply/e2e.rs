@@ -323,7 +323,7 @@ async fn apply_3_consecutives_schema_and_data_changes_on_clean_db() -> Result<()
         let table_definitions =
             get_surrealdb_table_definitions(Some(db_configuration.clone())).await?;
         ensure!(
-            table_definitions.len() == 7,
+            table_definitions.len() == 9,
             "First run, first migration: wrong number of tables"
         );
 
@@ -341,7 +341,7 @@ async fn apply_3_consecutives_schema_and_data_changes_on_clean_db() -> Result<()
         let table_definitions =
             get_surrealdb_table_definitions(Some(db_configuration.clone())).await?;
         ensure!(
-            table_definitions.len() == 8,
+            table_definitions.len() == 10,
             "First run, second migration: wrong number of tables"
         );
 
@@ -359,7 +359,7 @@ async fn apply_3_consecutives_schema_and_data_changes_on_clean_db() -> Result<()
         let table_definitions =
             get_surrealdb_table_definitions(Some(db_configuration.clone())).await?;
         ensure!(
-            table_definitions.len() == 9,
+            table_definitions.len() == 11,
             "First run, last migration: wrong number of tables"
         );
 
@@ -406,7 +406,7 @@ async fn apply_3_consecutives_schema_and_data_changes_on_clean_db() -> Result<()
     // Check db schema
     let table_definitions = get_surrealdb_table_definitions(Some(db_configuration.clone())).await?;
     ensure!(
-        table_definitions.len() == 7,
+        table_definitions.len() == 9,
         "Second run, first migration: wrong number of tables"
     );
 
@@ -436,7 +436,7 @@ async fn apply_3_consecutives_schema_and_data_changes_on_clean_db() -> Result<()
     // Check db schema
     let table_definitions = get_surrealdb_table_definitions(Some(db_configuration.clone())).await?;
     ensure!(
-        table_definitions.len() == 8,
+        table_definitions.len() == 10,
         "Second run, second migration: wrong number of tables"
     );
 
@@ -466,7 +466,7 @@ async fn apply_3_consecutives_schema_and_data_changes_on_clean_db() -> Result<()
     // Check db schema
     let table_definitions = get_surrealdb_table_definitions(Some(db_configuration.clone())).await?;
     ensure!(
-        table_definitions.len() == 9,
+        table_definitions.len() == 11,
         "Second run, last migration: wrong number of tables"
     );
 
@@ -578,7 +578,7 @@ async fn apply_3_consecutives_schema_and_data_changes_then_down_to_previous_migr
     };
 
     let table_definitions = get_surrealdb_table_definitions(Some(db_configuration)).await?;
-    ensure!(table_definitions.len() == 8, "Wrong number of tables");
+    ensure!(table_definitions.len() == 10, "Wrong number of tables");
 
     temp_dir.close()?;
 
@@ -643,7 +643,7 @@ async fn apply_3_consecutives_schema_and_data_changes_then_down_to_first_migrati
     };
 
     let table_definitions = get_surrealdb_table_definitions(Some(db_configuration)).await?;
-    ensure!(table_definitions.len() == 7, "Wrong number of tables");
+    ensure!(table_definitions.len() == 9, "Wrong number of tables");
 
     temp_dir.close()?;
 
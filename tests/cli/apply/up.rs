@@ -242,6 +242,34 @@ async fn apply_initial_migrations_in_dry_run() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn apply_initial_migrations_plan() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("apply").arg("--plan");
+
+    cmd.assert().try_success().and_then(|assert| {
+        assert.try_stdout(predicate::str::contains(format!(
+            "-- ScriptMigration Forward for {first_migration_name} --"
+        )))
+    })?;
+
+    let is_empty = is_surreal_db_empty(None, Some(db_name)).await?;
+    ensure!(is_empty, "SurrealDB should be empty");
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn apply_initial_migrations_in_dry_run_should_fail() -> Result<()> {
     let temp_dir = TempDir::new()?;
@@ -460,3 +488,33 @@ fn apply_with_recursive_schema_folders() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn display_warning_of_non_transactional_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    std::fs::write(
+        temp_dir
+            .join("migrations")
+            .join("20991231_120000_Backfill.notx.surql"),
+        "UPDATE post SET title = string::trim(title);",
+    )?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("apply");
+
+    cmd.assert().try_success().and_then(|assert| {
+        assert.try_stderr(predicate::str::contains(
+            "Warning: Executing migration outside of a transaction, a failure cannot be rolled back (20991231_120000_Backfill.notx)",
+        ))
+    })?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
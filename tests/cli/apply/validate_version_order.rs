@@ -39,3 +39,33 @@ fn fails_if_migrations_applied_with_new_migration_before_last_applied() -> Resul
 
     Ok(())
 }
+
+#[test]
+fn apply_new_migration_before_last_applied_in_out_of_order_mode() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+    std::fs::remove_file(first_migration_file)?;
+
+    apply_migrations(&temp_dir, &db_name)?;
+
+    empty_folder(&temp_dir)?;
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("apply")
+        .arg("--validate-version-order")
+        .arg("--out-of-order");
+
+    cmd.assert().try_success()?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
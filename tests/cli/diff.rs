@@ -1,6 +1,8 @@
 use assert_fs::TempDir;
-use color_eyre::eyre::{Error, Result};
+use color_eyre::eyre::{ensure, ContextCompat, Error, Result};
 use insta::{assert_snapshot, Settings};
+use predicates::prelude::*;
+use std::fs;
 
 use crate::helpers::*;
 
@@ -92,3 +94,221 @@ async fn apply_field_changes() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn apply_table_and_database_changes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+    execute_sql_statements(
+        "
+        DEFINE TABLE OVERWRITE permission SCHEMAFULL PERMISSIONS NONE;
+        DEFINE INDEX OVERWRITE unique_email ON user COLUMNS email;
+        DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello ' + $name; };
+        ",
+        DbInstance::Root,
+        &db_name,
+    )
+    .await?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("diff");
+
+    let assert = cmd.assert().try_success()?;
+    let stdout = get_stdout_str(assert)?;
+
+    let insta_settings = Settings::new();
+    insta_settings.bind(|| {
+        assert_snapshot!(stdout);
+        Ok::<(), Error>(())
+    })?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn no_changes_detected_in_traditional_project() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, true)?;
+    apply_migrations(&temp_dir, &db_name)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("diff");
+
+    let assert = cmd.assert().try_success()?;
+    let stdout = get_stdout_str(assert)?;
+
+    let insta_settings = Settings::new();
+    insta_settings.bind(|| {
+        assert_snapshot!(stdout);
+        Ok::<(), Error>(())
+    })?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn generate_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+    execute_sql_statements(
+        "
+        DEFINE FIELD OVERWRITE title ON post TYPE string DEFAULT 'Empty title';
+        DEFINE FUNCTION fn::greet($name: string) { RETURN 'Hello ' + $name; };
+        ",
+        DbInstance::Root,
+        &db_name,
+    )
+    .await?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("diff")
+        .arg("--generate-migration")
+        .arg("ReconcileSchema");
+
+    cmd.assert().try_success()?;
+
+    let migration_file = fs::read_dir(temp_dir.join("migrations"))?
+        .filter_map(|entry| entry.ok())
+        .find(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .ends_with("_ReconcileSchema.surql")
+        })
+        .context("Cannot find the generated migration file")?;
+
+    let migration_file_content = fs::read_to_string(migration_file.path())?;
+
+    ensure!(
+        migration_file_content.starts_with("DEFINE FIELD OVERWRITE title ON post"),
+        "Expected the changed field to be redefined"
+    );
+    ensure!(
+        migration_file_content.ends_with("# REMOVE FUNCTION fn::greet;"),
+        "Expected the REMOVE statement to be commented out"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn no_changes_detected_in_json_format() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("diff").arg("--format").arg("json");
+
+    let assert = cmd.assert().try_success()?;
+    let stdout = get_stdout_str(assert)?;
+
+    let insta_settings = Settings::new();
+    insta_settings.bind(|| {
+        assert_snapshot!(stdout);
+        Ok::<(), Error>(())
+    })?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn exit_code_without_changes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("diff").arg("--exit-code");
+
+    cmd.assert().try_success()?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn exit_code_with_changes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("diff")
+        .arg("--format")
+        .arg("json")
+        .arg("--exit-code");
+
+    let assert = cmd.assert().try_code(1)?.try_stderr("")?;
+    let stdout = get_stdout_str(assert)?;
+
+    let report: serde_json::Value = serde_json::from_str(&stdout)?;
+    let tables = report["tables"]
+        .as_array()
+        .context("Expected a list of tables")?;
+
+    ensure!(
+        tables.iter().any(|table| table["name"] == "post"),
+        "Expected the post table to be reported"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn exit_code_with_error() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+
+    add_migration_config_file_with_db_address(&temp_dir, "ws://localhost:12345")?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("diff").arg("--exit-code");
+
+    cmd.assert()
+        .try_code(2)?
+        .try_stderr(predicate::str::contains(
+            "There was an error processing a remote WS request",
+        ))?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+
+use crate::helpers::*;
+
+#[test]
+fn history_without_events() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_empty_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("history");
+
+    cmd.assert()
+        .try_success()
+        .and_then(|assert| assert.try_stdout("No migration events recorded yet!\n"))?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[test]
+fn history_of_blog_migrations() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+    apply_migrations_down(&temp_dir, &db_name, "0")?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+
+    cmd.arg("history").arg("--no-color");
+
+    let assert = cmd.assert().try_success()?;
+    let stdout = get_stdout_str(assert)?;
+
+    ensure!(stdout.matches("| up ").count() == 3, "Expected 3 up events");
+    ensure!(
+        stdout.matches("| down ").count() == 3,
+        "Expected 3 down events"
+    );
+    ensure!(!stdout.contains("failure"), "Expected no failed event");
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -33,7 +33,13 @@ fn list_blog_migrations() -> Result<()> {
 
     add_migration_config_file_with_db_name(&temp_dir, DbInstance::Root, &db_name)?;
     scaffold_blog_template(&temp_dir, false)?;
-    apply_migrations(&temp_dir, &db_name)?;
+
+    let mut cmd = create_cmd(&temp_dir)?;
+    cmd.arg("apply")
+        .arg("--db")
+        .arg(&db_name)
+        .env("SURREAL_MIG_EXECUTOR", "tester");
+    cmd.assert().try_success()?;
 
     let mut cmd = create_cmd(&temp_dir)?;
 
@@ -44,6 +50,7 @@ fn list_blog_migrations() -> Result<()> {
 
     let mut insta_settings = Settings::new();
     insta_settings.add_script_timestamp_filter();
+    insta_settings.add_migration_history_filters();
     insta_settings.bind(|| {
         assert_snapshot!(stdout);
         Ok::<(), Error>(())
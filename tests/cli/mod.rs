@@ -3,6 +3,7 @@ mod branch;
 mod create;
 mod definitions;
 mod diff;
+mod history;
 mod list;
 mod remove;
 mod scaffold;
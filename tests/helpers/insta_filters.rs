@@ -4,6 +4,8 @@ pub trait InstaSettingsExtensions {
     fn add_datetime_filter(&mut self);
     fn add_cli_location_filter(&mut self);
     fn add_script_timestamp_filter(&mut self);
+    fn add_migration_history_filters(&mut self);
+    fn add_script_migration_run_metadata_filters(&mut self);
 }
 
 impl InstaSettingsExtensions for Settings {
@@ -21,4 +23,29 @@ impl InstaSettingsExtensions for Settings {
         let regex = r"\d{8}_\d{6}";
         self.add_filter(regex, "[timestamp]");
     }
+
+    fn add_migration_history_filters(&mut self) {
+        let run_id_regex = r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}";
+        self.add_filter(run_id_regex, "[run_id]");
+
+        let duration_regex = r"\d+ms +";
+        self.add_filter(duration_regex, "[duration] ");
+
+        let version_regex = format!(r"{} +", regex::escape(env!("CARGO_PKG_VERSION")));
+        self.add_filter(&version_regex, "[version] ");
+    }
+
+    fn add_script_migration_run_metadata_filters(&mut self) {
+        let executed_by_regex = r#"executed_by: Some\("[^"]*"\)"#;
+        self.add_filter(executed_by_regex, r#"executed_by: Some("[executed_by]")"#);
+
+        let duration_regex = r"duration_ms: Some\(\d+\)";
+        self.add_filter(duration_regex, r#"duration_ms: Some("[duration]")"#);
+
+        let tool_version_regex = r#"tool_version: Some\("[^"]*"\)"#;
+        self.add_filter(tool_version_regex, r#"tool_version: Some("[version]")"#);
+
+        let run_id_regex = r#"run_id: Some\("[^"]*"\)"#;
+        self.add_filter(run_id_regex, r#"run_id: Some("[run_id]")"#);
+    }
 }
@@ -393,10 +393,15 @@ pub fn disable_checksum_capability(path: &Path) -> Result<()> {
         let schema_file = schemas_files_dir.join("script_migration.surql");
         let content = fs::read(&schema_file)?;
         let content = String::from_utf8(content)?;
-        let content = content.replace(
-            "DEFINE FIELD OVERWRITE checksum ON script_migration TYPE option<string>;",
-            "",
-        );
+        let content = content
+            .replace(
+                "DEFINE FIELD OVERWRITE checksum ON script_migration TYPE option<string>;",
+                "",
+            )
+            .replace(
+                "DEFINE FIELD OVERWRITE checksum_algorithm ON script_migration TYPE option<string>;",
+                "",
+            );
 
         fs::write(schema_file, content)?;
     }
@@ -0,0 +1,104 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use surrealdb_migrations::MigrationRunner;
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn baseline_migrations_without_executing_them() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let second_migration_name = get_second_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let migrations_baselined = runner.baseline(&second_migration_name).await?;
+    ensure!(
+        migrations_baselined.len() == 2,
+        "Expected 2 migrations to be baselined"
+    );
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 2,
+        "Expected 2 migrations to be recorded"
+    );
+    ensure!(
+        migrations_applied
+            .iter()
+            .all(|migration| migration.checksum.is_some()),
+        "Expected a checksum on each baselined migration"
+    );
+
+    let is_post_table_empty = is_surreal_table_empty(Some(("test", &db_name)), "post").await?;
+    ensure!(
+        is_post_table_empty,
+        "Expected baselined migrations not to be executed"
+    );
+
+    let report = runner.up().await?;
+    ensure!(
+        report.migrations.len() == 1,
+        "Expected only the remaining migration to be applied"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_baseline_when_history_is_not_empty() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let second_migration_name = get_second_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up_single().await?;
+
+    let result = runner.baseline(&second_migration_name).await;
+    ensure!(result.is_err(), "Expected the baseline to be refused");
+
+    let migrations_baselined = runner.force_baseline(&second_migration_name).await?;
+    ensure!(
+        migrations_baselined.len() == 1,
+        "Expected only the second migration to be baselined"
+    );
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 2,
+        "Expected 2 migrations to be recorded"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
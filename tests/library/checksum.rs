@@ -1,8 +1,9 @@
 use assert_fs::TempDir;
-use color_eyre::eyre::{Error, Result};
+use color_eyre::eyre::{ensure, Error, Result};
 use insta::{assert_ron_snapshot, Settings};
 use itertools::Itertools;
-use surrealdb_migrations::MigrationRunner;
+use std::fs;
+use surrealdb_migrations::{ChecksumAlgorithm, MigrationRunner};
 
 use crate::helpers::*;
 
@@ -32,6 +33,7 @@ async fn set_checksum_on_each_migration_file() -> Result<()> {
     let mut insta_settings = Settings::new();
     insta_settings.add_script_timestamp_filter();
     insta_settings.add_datetime_filter();
+    insta_settings.add_script_migration_run_metadata_filters();
     insta_settings.bind(|| {
         assert_ron_snapshot!(script_migrations
             .iter()
@@ -44,3 +46,48 @@ async fn set_checksum_on_each_migration_file() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn ignore_formatting_changes_with_normalized_checksum() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_checksum_algorithm(ChecksumAlgorithm::Normalized);
+
+    runner.up().await?;
+
+    let script_migrations = runner.list().await?;
+    ensure!(
+        script_migrations
+            .iter()
+            .all(|m| m.checksum_algorithm.as_deref() == Some("normalized")),
+        "Expected the normalized checksum algorithm to be stored"
+    );
+
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+    let first_migration_content = fs::read_to_string(&first_migration_file)?;
+    fs::write(
+        &first_migration_file,
+        format!("-- Create the admin user\n\n{first_migration_content}\n"),
+    )?;
+
+    runner.validate_checksum().await?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, ContextCompat, Result};
+use surrealdb::{engine::any::Any, Surreal};
+use surrealdb_migrations::{CodeMigration, MigrationFuture, MigrationRunner};
+
+use crate::helpers::*;
+
+const CODE_MIGRATION_NAME: &str = "29991231_235959_AddMarker";
+
+struct AddMarker;
+
+impl CodeMigration<Any> for AddMarker {
+    fn up<'a>(&'a self, db: &'a Surreal<Any>) -> MigrationFuture<'a> {
+        Box::pin(async move {
+            db.query("CREATE marker:code SET value = 1")
+                .await?
+                .check()?;
+            Ok(())
+        })
+    }
+
+    fn down<'a>(&'a self, db: &'a Surreal<Any>) -> Option<MigrationFuture<'a>> {
+        Some(Box::pin(async move {
+            db.query("DELETE marker:code").await?.check()?;
+            Ok(())
+        }))
+    }
+
+    fn has_down(&self) -> bool {
+        true
+    }
+}
+
+async fn count_markers(db: &Surreal<Any>) -> Result<usize> {
+    let mut response = db.query("SELECT * FROM marker").await?;
+    let markers: Vec<serde_json::Value> = response.take(0)?;
+
+    Ok(markers.len())
+}
+
+#[tokio::test]
+async fn apply_and_revert_code_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_code_migration(CODE_MIGRATION_NAME, &AddMarker);
+
+    let report = runner.up().await?;
+
+    ensure!(
+        report.migrations.len() == 4,
+        "Expected 4 migrations to be applied"
+    );
+
+    let last_migration = report
+        .migrations
+        .last()
+        .context("Cannot get last migration")?;
+
+    ensure!(
+        last_migration.name == CODE_MIGRATION_NAME,
+        "Expected code migration to be applied last"
+    );
+    ensure!(
+        last_migration.checksum.is_none(),
+        "Expected no checksum for a code migration"
+    );
+    ensure!(count_markers(&db).await? == 1, "Expected marker to exist");
+
+    let migrations_applied = runner.list().await?;
+
+    ensure!(
+        migrations_applied
+            .iter()
+            .any(|migration| migration.script_name == CODE_MIGRATION_NAME),
+        "Expected code migration to be tracked"
+    );
+
+    runner.validate_checksum().await?;
+    runner.down_single().await?;
+
+    ensure!(
+        count_markers(&db).await? == 0,
+        "Expected marker to be removed"
+    );
+
+    let migrations_applied = runner.list().await?;
+
+    ensure!(
+        migrations_applied.len() == 3,
+        "Expected code migration to be reverted"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_fail_to_apply_code_migration_with_the_name_of_a_migration_file() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_code_migration(&first_migration_name, &AddMarker);
+
+    let result = runner.up().await;
+
+    ensure!(result.is_err(), "Expected the migration run to fail");
+    ensure!(
+        result.unwrap_err().to_string()
+            == format!(
+                "The code migration '{first_migration_name}' has the same name as a migration file."
+            ),
+        "Expected the code migration name to be rejected"
+    );
+    ensure!(
+        runner.list().await?.is_empty(),
+        "Expected no migration to be applied"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redo_code_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_code_migration(CODE_MIGRATION_NAME, &AddMarker);
+
+    runner.up().await?;
+
+    db.query("DELETE marker:code").await?.check()?;
+
+    let report = runner.redo(CODE_MIGRATION_NAME).await?;
+
+    ensure!(
+        report.migrations.len() == 1,
+        "Expected the code migration to be re-applied"
+    );
+    ensure!(
+        count_markers(&db).await? == 1,
+        "Expected marker to be created again"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use surrealdb_migrations::{MigrationError, MigrationRunner};
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn fake_and_unfake_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.fake(&first_migration_name).await?;
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 1,
+        "Expected 1 migration to be recorded"
+    );
+    ensure!(
+        migrations_applied[0].script_name == first_migration_name,
+        "Expected the faked migration to be recorded"
+    );
+    ensure!(
+        migrations_applied[0].checksum.is_some(),
+        "Expected a checksum on the faked migration"
+    );
+
+    let is_user_table_empty = is_surreal_table_empty(Some(("test", &db_name)), "user").await?;
+    ensure!(
+        is_user_table_empty,
+        "Expected the faked migration not to be executed"
+    );
+
+    runner.unfake(&first_migration_name).await?;
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.is_empty(),
+        "Expected the migration to be removed from history"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_fake_unknown_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let result = runner.fake("unknown-migration").await;
+    ensure!(
+        matches!(result, Err(MigrationError::UnknownMigration { .. })),
+        "Expected an unknown migration error"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_unfake_migration_not_applied() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let result = runner.unfake(&first_migration_name).await;
+    ensure!(
+        matches!(result, Err(MigrationError::MigrationNotApplied { .. })),
+        "Expected a migration not applied error"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
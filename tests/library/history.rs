@@ -0,0 +1,166 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, ContextCompat, Result};
+use std::fs;
+use surrealdb_migrations::MigrationRunner;
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn record_up_and_down_events() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let third_migration_name = get_third_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_executor("tester");
+
+    runner.up().await?;
+    runner.down_single().await?;
+
+    let events = runner.history().await?;
+
+    ensure!(events.len() == 4, "Expected 4 events to be recorded");
+    ensure!(
+        events.iter().take(3).all(|event| event.action == "up"),
+        "Expected the first events to be up actions"
+    );
+    ensure!(
+        events
+            .iter()
+            .all(|event| event.outcome == "success" && event.error.is_none()),
+        "Expected all events to be successful"
+    );
+    ensure!(
+        events
+            .iter()
+            .all(|event| event.executed_by.as_deref() == Some("tester")),
+        "Expected all events to be executed by the configured executor"
+    );
+
+    let last_event = events.last().context("Cannot get last event")?;
+
+    ensure!(
+        last_event.action == "down",
+        "Expected the last event to be a down action"
+    );
+    ensure!(
+        last_event.script_name == third_migration_name,
+        "Expected the last migration to be reverted"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_redo_event() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+
+    let second_migration_name = get_second_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.redo(&second_migration_name).await?;
+
+    let events = runner.history().await?;
+    let last_event = events.last().context("Cannot get last event")?;
+
+    ensure!(
+        last_event.action == "redo",
+        "Expected the last event to be a redo action"
+    );
+    ensure!(
+        last_event.script_name == second_migration_name,
+        "Expected the second migration to be redone"
+    );
+    ensure!(
+        last_event.outcome == "success",
+        "Expected the redo to be successful"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_failed_migration_event() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    fs::write(
+        temp_dir
+            .join("migrations")
+            .join("20991231_120000_Failure.surql"),
+        "THROW 'Migration failed';",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let result = runner.up().await;
+    ensure!(result.is_err(), "Expected the migration run to fail");
+
+    let events = runner.history().await?;
+    let last_event = events.last().context("Cannot get last event")?;
+
+    ensure!(
+        last_event.script_name == "20991231_120000_Failure",
+        "Expected the failed migration to be recorded"
+    );
+    ensure!(
+        last_event.outcome == "failure",
+        "Expected the event to be recorded as a failure"
+    );
+    ensure!(
+        last_event
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("Migration failed")),
+        "Expected the error message to be recorded"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
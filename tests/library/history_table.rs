@@ -0,0 +1,68 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use std::{fs, path::Path};
+use surrealdb_migrations::MigrationRunner;
+
+use crate::helpers::*;
+
+const HISTORY_TABLE: &str = "blog_migration";
+
+fn use_history_table_in_schemas(path: &Path) -> Result<()> {
+    let schemas_dir = path.join("schemas");
+
+    let script_migration_file = schemas_dir.join("script_migration.surql");
+    let content = fs::read_to_string(&script_migration_file)?;
+
+    fs::write(
+        schemas_dir.join(format!("{HISTORY_TABLE}.surql")),
+        content.replace("script_migration", HISTORY_TABLE),
+    )?;
+    fs::remove_file(script_migration_file)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn apply_migrations_with_custom_history_table() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    use_history_table_in_schemas(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .use_history_table(HISTORY_TABLE);
+
+    runner.up().await?;
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "Expected 3 migrations in the custom history table"
+    );
+
+    let status = runner.status().await?;
+    ensure!(status.is_up_to_date(), "Expected no pending migration");
+
+    let default_migrations_applied = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .list()
+        .await?;
+    ensure!(
+        default_migrations_applied.is_empty(),
+        "Expected no migration in the default history table"
+    );
+
+    Ok(())
+}
@@ -132,3 +132,77 @@ async fn list_blog_migrations() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn list_migrations_with_run_metadata() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_executor("deploy-pipeline")
+        .up()
+        .await?;
+
+    let migrations_applied = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .list()
+        .await?;
+
+    ensure!(
+        migrations_applied.len() == 3,
+        "Expected 3 migrations to be applied"
+    );
+
+    let first_migration = migrations_applied
+        .first()
+        .context("Cannot get first migration")?;
+
+    ensure!(
+        migrations_applied
+            .iter()
+            .all(|migration| migration.executed_by.as_deref() == Some("deploy-pipeline")),
+        "Expected migrations to be executed by the configured executor"
+    );
+    ensure!(
+        migrations_applied
+            .iter()
+            .all(|migration| migration.tool_version.as_deref() == Some(env!("CARGO_PKG_VERSION"))),
+        "Expected migrations to store the tool version"
+    );
+    ensure!(
+        migrations_applied
+            .iter()
+            .all(|migration| migration.tags == Some(vec!["*".to_string()])),
+        "Expected migrations to store the tags of the run"
+    );
+    ensure!(
+        migrations_applied
+            .iter()
+            .all(|migration| migration.duration_ms.is_some()),
+        "Expected migrations to store their duration"
+    );
+    ensure!(
+        first_migration.run_id.is_some()
+            && migrations_applied
+                .iter()
+                .all(|migration| migration.run_id == first_migration.run_id),
+        "Expected migrations to share the same run id"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, eyre, Result};
+use std::time::Duration;
+use surrealdb::{engine::any::Any, Surreal};
+use surrealdb_migrations::{CodeMigration, MigrationError, MigrationFuture, MigrationRunner};
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn release_lock_after_applying_migrations() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .up()
+        .await?;
+
+    let is_lock_table_empty =
+        is_surreal_table_empty(Some(("test", db_name.as_str())), "migration_lock").await?;
+    ensure!(
+        is_lock_table_empty,
+        "The lock should be removed after the migration run"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fails_to_apply_migrations_when_lock_is_held() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    db.query(
+        "CREATE migration_lock:script_migration SET owner = 'other', acquired_at = time::now(), expires_at = time::now() + 1h",
+    )
+    .await?
+    .check()?;
+
+    let result = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_lock_timeout(Duration::from_secs(1))
+        .up()
+        .await;
+
+    match result {
+        Err(MigrationError::LockTimeout { owner, .. }) => {
+            ensure!(owner == "other", "The lock should be held by 'other'");
+        }
+        _ => return Err(eyre!("Expected a lock timeout error")),
+    }
+
+    let migrations_applied = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .list()
+        .await?;
+    ensure!(
+        migrations_applied.is_empty(),
+        "No migration should be applied while the lock is held"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn take_over_stale_lock() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    db.query(
+        "CREATE migration_lock:script_migration SET owner = 'crashed', acquired_at = time::now() - 2h, expires_at = time::now() - 1h",
+    )
+    .await?
+    .check()?;
+
+    MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_lock_timeout(Duration::from_secs(1))
+        .up()
+        .await?;
+
+    let migrations_applied = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .list()
+        .await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "The stale lock should have been taken over"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn apply_migrations_without_lock() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    db.query(
+        "CREATE migration_lock:script_migration SET owner = 'other', acquired_at = time::now(), expires_at = time::now() + 1h",
+    )
+    .await?
+    .check()?;
+
+    MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .without_lock()
+        .up()
+        .await?;
+
+    let migrations_applied = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .list()
+        .await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "Migrations should be applied even if the lock is held"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn apply_migrations_once_lock_is_released_by_another_run() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    db.query(
+        "CREATE migration_lock:script_migration SET owner = 'other', acquired_at = time::now(), expires_at = time::now() + 1h",
+    )
+    .await?
+    .check()?;
+
+    let other_db = db.clone();
+    let release = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        other_db
+            .query("DELETE migration_lock:script_migration WHERE owner = 'other'")
+            .await
+    });
+
+    MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_lock_timeout(Duration::from_secs(10))
+        .up()
+        .await?;
+
+    release.await??.check()?;
+
+    let migrations_applied = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .list()
+        .await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "Migrations should be applied once the lock is released"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+struct SlowMigration;
+
+impl CodeMigration<Any> for SlowMigration {
+    fn up<'a>(&'a self, _db: &'a Surreal<Any>) -> MigrationFuture<'a> {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            Ok(())
+        })
+    }
+
+    fn down<'a>(&'a self, _db: &'a Surreal<Any>) -> Option<MigrationFuture<'a>> {
+        None
+    }
+}
+
+#[tokio::test]
+async fn renew_lock_during_long_migration_run() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_lock_lease(Duration::from_secs(1))
+        .with_code_migration("29991231_235959_SlowMigration", &SlowMigration);
+
+    let other_runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_lock_timeout(Duration::from_secs(1));
+
+    let (result, other_result) = tokio::join!(runner.up(), async {
+        // 💡 the initial lease has expired at this point, the lock is only held if it has been renewed
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        other_runner.fake(&first_migration_name).await
+    });
+
+    result?;
+
+    match other_result {
+        Err(MigrationError::LockTimeout { .. }) => {}
+        _ => return Err(eyre!("Expected a lock timeout error")),
+    }
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fails_to_fake_migration_when_lock_is_held() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    db.query(
+        "CREATE migration_lock:script_migration SET owner = 'other', acquired_at = time::now(), expires_at = time::now() + 1h",
+    )
+    .await?
+    .check()?;
+
+    let result = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_lock_timeout(Duration::from_secs(1))
+        .fake(&first_migration_name)
+        .await;
+
+    match result {
+        Err(MigrationError::LockTimeout { owner, .. }) => {
+            ensure!(owner == "other", "The lock should be held by 'other'");
+        }
+        _ => return Err(eyre!("Expected a lock timeout error")),
+    }
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -1,13 +1,29 @@
+mod baseline;
 mod checksum;
+mod code_migration;
 mod down_single;
 mod down_to;
+mod fake;
+mod history;
+mod history_table;
 mod list;
 mod load_files;
+mod lock;
+mod non_transactional;
+mod observer;
+mod out_of_order;
+mod plan;
 mod redo;
+mod repair;
+mod report;
 mod reset;
+mod source;
+mod status;
 mod tags;
+mod tracing;
 mod traditional;
 mod up;
+mod up_atomic;
 mod up_single;
 mod up_to;
 mod use_config_file;
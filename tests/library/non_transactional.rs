@@ -0,0 +1,96 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, eyre, Result};
+use std::fs;
+use surrealdb_migrations::{MigrationError, MigrationRunner};
+
+use crate::helpers::*;
+
+const MIGRATION_NAME: &str = "20991231_120000_Backfill.notx";
+
+#[tokio::test]
+async fn apply_non_transactional_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    fs::write(
+        temp_dir
+            .join("migrations")
+            .join(format!("{MIGRATION_NAME}.surql")),
+        "UPDATE post SET title = string::trim(title);",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up().await?;
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied
+            .iter()
+            .any(|migration| migration.script_name == MIGRATION_NAME),
+        "The non-transactional migration should be recorded once applied"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn do_not_record_failed_non_transactional_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    fs::write(
+        temp_dir
+            .join("migrations")
+            .join(format!("{MIGRATION_NAME}.surql")),
+        "THROW 'Backfill failed';",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    match runner.up().await {
+        Err(MigrationError::NonTransactionalMigrationFailed { migration, .. }) => {
+            ensure!(
+                migration == MIGRATION_NAME,
+                "The error should refer to the failed migration"
+            );
+        }
+        _ => return Err(eyre!("Expected a non-transactional migration failure")),
+    }
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "The failed non-transactional migration should not be recorded"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use std::sync::Mutex;
+use surrealdb_migrations::{
+    MigrationDirection, MigrationError, MigrationObserver, MigrationReport, MigrationRunner,
+};
+
+use crate::helpers::*;
+
+#[derive(Default)]
+struct RecordingObserver {
+    events: Mutex<Vec<String>>,
+}
+
+impl RecordingObserver {
+    fn record(&self, event: String) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn events(&self) -> Vec<String> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl MigrationObserver for RecordingObserver {
+    fn before_migration(&self, name: &str, direction: MigrationDirection) {
+        self.record(format!("before {name} {direction:?}"));
+    }
+
+    fn after_migration(&self, name: &str, direction: MigrationDirection, _: &MigrationReport) {
+        self.record(format!("after {name} {direction:?}"));
+    }
+
+    fn on_error(&self, name: &str, direction: MigrationDirection, _: &MigrationError) {
+        self.record(format!("error {name} {direction:?}"));
+    }
+
+    fn on_schema_applied(&self) {
+        self.record("schema applied".to_string());
+    }
+}
+
+#[tokio::test]
+async fn observe_applied_and_reverted_migrations() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let observer = RecordingObserver::default();
+
+    let runner = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_observer(&observer);
+
+    runner.up_single().await?;
+    runner.down_single().await?;
+
+    let events = observer.events();
+
+    ensure!(
+        events
+            == vec![
+                format!("before {first_migration_name} Forward"),
+                format!("after {first_migration_name} Forward"),
+                "schema applied".to_string(),
+                format!("before {first_migration_name} Backward"),
+                format!("after {first_migration_name} Backward"),
+                "schema applied".to_string(),
+            ],
+        "Unexpected observer events: {:?}",
+        events
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
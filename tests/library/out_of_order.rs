@@ -0,0 +1,122 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use surrealdb_migrations::MigrationRunner;
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn apply_migration_before_last_applied_by_default() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+    std::fs::remove_file(first_migration_file)?;
+
+    runner.up().await?;
+
+    empty_folder(&temp_dir)?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let report = runner.up().await?;
+
+    ensure!(
+        report.migrations.len() == 1,
+        "Expected only the missing migration to be applied"
+    );
+    ensure!(
+        report.migrations[0].name == first_migration_name,
+        "Expected the first migration to be applied"
+    );
+    ensure!(
+        report.migrations[0].out_of_order,
+        "Expected the migration to be reported as applied out of order"
+    );
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "Expected all migrations to be applied"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn apply_migration_before_last_applied_in_out_of_order_mode() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+    std::fs::remove_file(first_migration_file)?;
+
+    runner.up().await?;
+
+    empty_folder(&temp_dir)?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let report = runner.allow_out_of_order().up().await?;
+
+    ensure!(
+        report.migrations.len() == 1,
+        "Expected only the missing migration to be applied"
+    );
+    ensure!(
+        report.migrations[0].name == first_migration_name,
+        "Expected the first migration to be applied"
+    );
+    ensure!(
+        report.migrations[0].out_of_order,
+        "Expected the migration to be reported as applied out of order"
+    );
+
+    let migrations_applied = MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .list()
+        .await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "Expected all migrations to be applied"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, ContextCompat, Result};
+use surrealdb_migrations::{
+    ApplyOperation, MigrationDirection, MigrationPlanStepKind, MigrationRunner,
+};
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn plan_initial_migrations() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let plan = runner.plan(ApplyOperation::Up).await?;
+
+    let script_migration_steps = plan
+        .steps
+        .iter()
+        .filter(|step| step.kind == MigrationPlanStepKind::ScriptMigration)
+        .collect::<Vec<_>>();
+
+    ensure!(
+        script_migration_steps.len() == 3,
+        "Expected 3 migrations to be planned"
+    );
+
+    let first_step = plan.steps.first().context("Expected a planned step")?;
+
+    ensure!(
+        first_step.kind == MigrationPlanStepKind::Schemas,
+        "Expected schemas to be applied first"
+    );
+    ensure!(
+        first_step.migration.as_deref() == Some(first_migration_name.as_str()),
+        "Expected first step to be part of {}",
+        first_migration_name
+    );
+    ensure!(
+        first_step.direction == MigrationDirection::Forward,
+        "Expected steps to be applied forward"
+    );
+    ensure!(
+        first_step.to_surql().contains("DEFINE TABLE"),
+        "Expected rendered SurrealQL"
+    );
+
+    let migrations_applied = runner.list().await?;
+
+    ensure!(
+        migrations_applied.is_empty(),
+        "Expected no migration to be applied"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn plan_does_not_change_project_files() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let snapshot_dir = TempDir::new()?;
+    copy_folder(&temp_dir, &snapshot_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let plan = runner.plan(ApplyOperation::Up).await?;
+
+    ensure!(!plan.steps.is_empty(), "Expected migrations to be planned");
+
+    let temp_dir_name = temp_dir.file_name().context("Expected a folder name")?;
+
+    ensure!(
+        !is_folder_exists(&temp_dir.join("migrations/definitions"))?,
+        "Expected no definition file to be created"
+    );
+    ensure!(
+        are_folders_equivalent(&temp_dir, &snapshot_dir.join(temp_dir_name))?,
+        "Expected project files to be unchanged"
+    );
+
+    temp_dir.close()?;
+    snapshot_dir.close()?;
+
+    Ok(())
+}
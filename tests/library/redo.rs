@@ -1,7 +1,7 @@
 use assert_fs::TempDir;
 use color_eyre::Result;
 use insta::assert_snapshot;
-use surrealdb_migrations::MigrationRunner;
+use surrealdb_migrations::{MigrationError, MigrationRunner};
 
 use crate::helpers::*;
 
@@ -27,8 +27,11 @@ async fn should_fail_to_redo_inexistant_migration() -> Result<()> {
 
     let result = runner.redo("unknown-migration").await;
 
-    assert!(result.is_err());
-    assert_snapshot!(result.unwrap_err(), @"The migration file was not found");
+    assert!(matches!(
+        result,
+        Err(MigrationError::UnknownMigration { ref migration }) if migration == "unknown-migration"
+    ));
+    assert_snapshot!(result.unwrap_err(), @"The migration file 'unknown-migration' was not found");
 
     temp_dir.close()?;
 
@@ -94,3 +97,38 @@ async fn should_redo_migration_with_success() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn should_update_checksum_of_edited_migration_on_redo() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+    std::fs::write(
+        first_migration_file,
+        "CREATE permission:new SET name = 'new';",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.redo(&first_migration_name).await?;
+    runner.validate_checksum().await?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use std::fs;
+use surrealdb::{engine::any::Any, Surreal};
+use surrealdb_migrations::{CodeMigration, MigrationFuture, MigrationRunner};
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn repair_migrations_history() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up().await?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+    let third_migration_name = get_third_migration_name(&temp_dir)?;
+
+    let first_migration_content = fs::read_to_string(&first_migration_file)?;
+    fs::write(
+        &first_migration_file,
+        format!("-- Create the admin user\n{first_migration_content}"),
+    )?;
+
+    let third_migration_file = temp_dir
+        .join("migrations")
+        .join(format!("{third_migration_name}.surql"));
+    fs::remove_file(third_migration_file)?;
+
+    let result = runner.validate_checksum().await;
+    ensure!(result.is_err(), "Expected the checksum validation to fail");
+
+    let repair = runner.repair().await?;
+    ensure!(
+        repair.checksum_mismatches == vec![first_migration_name],
+        "Expected the checksum of the first migration to be updated"
+    );
+    ensure!(
+        repair.missing_checksums.is_empty(),
+        "Expected no checksum to be added"
+    );
+    ensure!(
+        repair.missing_files == vec![third_migration_name],
+        "Expected the third migration to be removed from history"
+    );
+
+    runner.validate_checksum().await?;
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 2,
+        "Expected 2 migrations to be recorded"
+    );
+
+    let repair = runner.repair().await?;
+    ensure!(repair.is_empty(), "Expected nothing left to repair");
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn preview_repair_without_changing_history() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up().await?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+
+    let first_migration_content = fs::read_to_string(&first_migration_file)?;
+    fs::write(
+        &first_migration_file,
+        format!("-- Create the admin user\n{first_migration_content}"),
+    )?;
+
+    let repair = runner.preview_repair().await?;
+    ensure!(
+        repair.checksum_mismatches == vec![first_migration_name],
+        "Expected the checksum mismatch of the first migration to be listed"
+    );
+
+    let result = runner.validate_checksum().await;
+    ensure!(
+        result.is_err(),
+        "Expected the checksum to be left unchanged by the preview"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+struct AddMarker;
+
+impl CodeMigration<Any> for AddMarker {
+    fn up<'a>(&'a self, db: &'a Surreal<Any>) -> MigrationFuture<'a> {
+        Box::pin(async move {
+            db.query("CREATE marker:code SET value = 1")
+                .await?
+                .check()?;
+            Ok(())
+        })
+    }
+
+    fn down<'a>(&'a self, _db: &'a Surreal<Any>) -> Option<MigrationFuture<'a>> {
+        None
+    }
+}
+
+#[tokio::test]
+async fn keep_history_of_code_migrations() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .with_code_migration("29991231_235959_AddMarker", &AddMarker)
+        .up()
+        .await?;
+
+    // 💡 same as the CLI, which does not know about code migrations
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let repair = runner.repair().await?;
+    ensure!(
+        repair.missing_files.is_empty(),
+        "Expected the code migration not to be removed from history"
+    );
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 4,
+        "Expected 4 migrations to be recorded"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
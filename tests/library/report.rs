@@ -0,0 +1,117 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, ContextCompat, Result};
+use surrealdb_migrations::{MigrationDirection, MigrationRunner};
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn report_applied_migrations() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let report = runner.up().await?;
+
+    ensure!(!report.dry_run, "Expected a real run");
+    ensure!(report.schemas_applied, "Expected schemas to be applied");
+    ensure!(report.events_applied, "Expected events to be applied");
+    ensure!(
+        report.migrations.len() == 3,
+        "Expected 3 migrations to be reported"
+    );
+
+    let first_migration = report
+        .migrations
+        .first()
+        .context("Cannot get first migration")?;
+
+    ensure!(
+        first_migration.name == first_migration_name,
+        "Expected first migration to be {}",
+        first_migration_name
+    );
+    ensure!(
+        first_migration.direction == MigrationDirection::Forward,
+        "Expected first migration to be applied forward"
+    );
+    ensure!(
+        first_migration.checksum.is_some(),
+        "Expected checksum to be stored"
+    );
+    ensure!(
+        first_migration.statements_count > 0,
+        "Expected statements to be counted"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn report_reverted_migration() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+    apply_migrations(&temp_dir, &db_name)?;
+
+    let third_migration_name = get_third_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let report = runner.down_single().await?;
+
+    ensure!(
+        report.migrations.len() == 1,
+        "Expected 1 migration to be reported"
+    );
+
+    let migration = report
+        .migrations
+        .first()
+        .context("Cannot get reverted migration")?;
+
+    ensure!(
+        migration.name == third_migration_name,
+        "Expected reverted migration to be {}",
+        third_migration_name
+    );
+    ensure!(
+        migration.direction == MigrationDirection::Backward,
+        "Expected migration to be reverted"
+    );
+    ensure!(
+        migration.checksum.is_none(),
+        "Expected no checksum for a reverted migration"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
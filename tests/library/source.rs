@@ -0,0 +1,93 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use surrealdb_migrations::{FileSystemSource, InMemorySource, MigrationRunner};
+
+use crate::helpers::*;
+
+const SCRIPT_MIGRATION_SCHEMA: &str =
+    include_str!("../../templates/empty/schemas/script_migration.surql");
+
+fn create_in_memory_source() -> Result<InMemorySource> {
+    let initial_definition = serde_json::json!({
+        "schemas": SCRIPT_MIGRATION_SCHEMA,
+        "events": "",
+    });
+
+    let source = InMemorySource::new()
+        .with_file("schemas/script_migration.surql", SCRIPT_MIGRATION_SCHEMA)
+        .with_file(
+            "migrations/20231225_120000_AddMarker.surql",
+            "CREATE marker:memory SET value = 1;",
+        )
+        .with_file(
+            "migrations/down/20231225_120000_AddMarker.surql",
+            "DELETE marker:memory;",
+        )
+        .with_file(
+            "migrations/definitions/_initial.json",
+            serde_json::to_string(&initial_definition)?,
+        );
+
+    Ok(source)
+}
+
+#[tokio::test]
+async fn apply_and_revert_migrations_from_in_memory_source() -> Result<()> {
+    let db_name = generate_random_db_name()?;
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).with_source(create_in_memory_source()?);
+
+    let report = runner.up().await?;
+
+    ensure!(
+        report.migrations.len() == 1,
+        "Expected 1 migration to be applied"
+    );
+
+    let status = runner.status().await?;
+
+    ensure!(status.is_up_to_date(), "Expected no pending migration");
+
+    let report = runner.reset().await?;
+
+    ensure!(
+        report.migrations.len() == 1,
+        "Expected 1 migration to be reverted"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn apply_migrations_from_filesystem_source() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let report = MigrationRunner::new(&db)
+        .with_source(FileSystemSource::new(temp_dir.path()))
+        .up()
+        .await?;
+
+    ensure!(
+        report.migrations.len() == 3,
+        "Expected 3 migrations to be applied"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, ContextCompat, Result};
+use surrealdb_migrations::MigrationRunner;
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn status_with_migrations_left() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let first_migration_name = get_first_migration_name(&temp_dir)?;
+    let second_migration_name = get_second_migration_name(&temp_dir)?;
+    let third_migration_name = get_third_migration_name(&temp_dir)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up_single().await?;
+
+    let status = runner.status().await?;
+
+    ensure!(status.applied == 1, "Expected 1 migration to be applied");
+    ensure!(
+        status.pending == vec![second_migration_name, third_migration_name],
+        "Expected 2 migrations to be pending"
+    );
+    ensure!(status.missing.is_empty(), "Expected no missing file");
+    ensure!(status.supports_checksum, "Expected checksum support");
+    ensure!(
+        status.use_migration_definitions,
+        "Expected definition files to be used"
+    );
+    ensure!(!status.is_up_to_date(), "Expected status to be outdated");
+
+    let last_applied = status
+        .last_applied
+        .context("Expected a migration to be applied")?;
+
+    ensure!(
+        last_applied.script_name == first_migration_name,
+        "Expected last applied migration to be {}",
+        first_migration_name
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn status_without_history_table() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let status = runner.status().await?;
+
+    ensure!(status.applied == 0, "Expected no migration to be applied");
+    ensure!(
+        status.pending.len() == 3,
+        "Expected all migrations to be pending"
+    );
+    ensure!(
+        status.last_applied.is_none(),
+        "Expected no last applied migration"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+use surrealdb_migrations::MigrationRunner;
+use tracing::{
+    field::{Field, Visit},
+    span::Attributes,
+    Event, Id, Subscriber,
+};
+use tracing_subscriber::{layer::Context, layer::SubscriberExt, Layer};
+
+use crate::helpers::*;
+
+#[derive(Clone, Default)]
+struct RecordingLayer {
+    records: Arc<Mutex<Vec<String>>>,
+}
+
+impl RecordingLayer {
+    fn records(&self) -> Vec<String> {
+        self.records.lock().unwrap().clone()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecordingLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        self.records
+            .lock()
+            .unwrap()
+            .push(format!("span {}", attrs.metadata().name()));
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        if let Some(message) = visitor.message {
+            self.records.lock().unwrap().push(message);
+        }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: Option<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{value:?}"));
+        }
+    }
+}
+
+#[tokio::test]
+async fn trace_applied_migrations() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let layer = RecordingLayer::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(layer.clone()));
+
+    MigrationRunner::new(&db)
+        .use_config_file(&config_file_path)
+        .up()
+        .await?;
+
+    let records = layer.records();
+
+    ensure!(
+        records.iter().filter(|r| *r == "span apply").count() == 1,
+        "Expected a single 'apply' span"
+    );
+    ensure!(
+        records.iter().filter(|r| *r == "span migration").count() == 3,
+        "Expected a 'migration' span per migration"
+    );
+    ensure!(
+        records.contains(&"Executing migration AddAdminUser...".to_string()),
+        "Expected an event for the 'AddAdminUser' migration"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
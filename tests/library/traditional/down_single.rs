@@ -1,5 +1,6 @@
 use assert_fs::TempDir;
 use color_eyre::{eyre::ensure, Result};
+use std::{collections::HashMap, fs};
 use surrealdb_migrations::MigrationRunner;
 
 use crate::helpers::*;
@@ -36,3 +37,143 @@ async fn apply_revert_single_migration() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn apply_revert_single_migration_without_down_file() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, true)?;
+
+    fs::write(
+        temp_dir
+            .join("migrations")
+            .join("20991231_120000_AddArchive.surql"),
+        "DEFINE TABLE archive SCHEMALESS;
+DEFINE FIELD archived ON post TYPE option<bool>;",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up().await?;
+
+    let is_table_created =
+        get_surrealdb_table_exists(Some(configuration.clone()), "archive").await?;
+    ensure!(
+        is_table_created,
+        "Expected the 'archive' table to be created"
+    );
+
+    runner.down_single().await?;
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 4,
+        "Expected 4 migrations to be applied"
+    );
+
+    let is_table_created = get_surrealdb_table_exists(Some(configuration), "archive").await?;
+    ensure!(
+        !is_table_created,
+        "Expected the 'archive' table to be removed using the schema snapshot"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn store_schema_snapshot_only_for_migrations_without_down_file() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, true)?;
+
+    fs::write(
+        temp_dir
+            .join("migrations")
+            .join("20991231_120000_AddArchive.surql"),
+        "DEFINE TABLE archive SCHEMALESS;",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up().await?;
+
+    let migrations_with_snapshot = runner
+        .list()
+        .await?
+        .into_iter()
+        .filter(|migration| migration.schema_snapshot.is_some())
+        .map(|migration| migration.script_name)
+        .collect::<Vec<_>>();
+
+    ensure!(
+        migrations_with_snapshot == vec!["20991231_120000_AddArchive".to_string()],
+        "Expected a schema snapshot only for the migration without down file"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn apply_revert_single_migration_without_down_file_removes_access() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, true)?;
+
+    fs::write(
+        temp_dir
+            .join("migrations")
+            .join("20991231_120000_AddArchiveAccess.surql"),
+        "DEFINE ACCESS archive ON DATABASE TYPE JWT ALGORITHM HS512 KEY 'secret';",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    runner.up().await?;
+    runner.down_single().await?;
+
+    let mut response = db.query("INFO FOR DB;").await?;
+    let accesses: Option<HashMap<String, String>> = response.take("accesses")?;
+
+    ensure!(
+        accesses.unwrap_or_default().is_empty(),
+        "Expected the 'archive' access to be removed using the schema snapshot"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -28,7 +28,7 @@ async fn should_fail_to_redo_inexistant_migration() -> Result<()> {
     let result = runner.redo("unknown-migration").await;
 
     assert!(result.is_err());
-    assert_snapshot!(result.unwrap_err(), @"The migration file was not found");
+    assert_snapshot!(result.unwrap_err(), @"The migration file 'unknown-migration' was not found");
 
     temp_dir.close()?;
 
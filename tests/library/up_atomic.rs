@@ -0,0 +1,82 @@
+use assert_fs::TempDir;
+use color_eyre::eyre::{ensure, Result};
+use std::fs;
+use surrealdb_migrations::MigrationRunner;
+
+use crate::helpers::*;
+
+#[tokio::test]
+async fn apply_all_migrations_in_single_transaction() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let report = runner.up_atomic().await?;
+    ensure!(
+        report.migrations.len() == 3,
+        "Expected 3 migrations applied"
+    );
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.len() == 3,
+        "Expected 3 migrations to be recorded"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn apply_no_migration_if_one_fails_in_single_transaction() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    fs::write(
+        temp_dir
+            .join("migrations")
+            .join("20991231_120000_Failure.surql"),
+        "THROW 'Migration failed';",
+    )?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let result = runner.up_atomic().await;
+    ensure!(result.is_err(), "Expected the migration run to fail");
+
+    let migrations_applied = runner.list().await?;
+    ensure!(
+        migrations_applied.is_empty(),
+        "Expected no migration to be applied"
+    );
+
+    temp_dir.close()?;
+
+    Ok(())
+}
@@ -137,6 +137,42 @@ async fn fails_if_migrations_applied_with_new_migration_before_last_applied() ->
     Ok(())
 }
 
+#[tokio::test]
+async fn ok_if_new_migration_before_last_applied_in_out_of_order_mode() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let db_name = generate_random_db_name()?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    let config_file_path = temp_dir.join(".surrealdb");
+
+    let configuration = SurrealdbConfiguration {
+        db: Some(db_name.to_string()),
+        ..Default::default()
+    };
+
+    let db = create_surrealdb_client(&configuration).await?;
+
+    let runner = MigrationRunner::new(&db).use_config_file(&config_file_path);
+
+    let first_migration_file = get_first_migration_file(&temp_dir)?;
+    std::fs::remove_file(first_migration_file)?;
+
+    runner.up().await?;
+
+    empty_folder(&temp_dir)?;
+
+    add_migration_config_file_with_db_name_in_dir(&temp_dir, DbInstance::Root, &db_name)?;
+    scaffold_blog_template(&temp_dir, false)?;
+
+    runner.allow_out_of_order().validate_version_order().await?;
+
+    temp_dir.close()?;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn ok_if_migrations_applied_but_no_new_migration_with_inlined_down_files() -> Result<()> {
     let temp_dir = TempDir::new()?;